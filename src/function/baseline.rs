@@ -0,0 +1,90 @@
+// baseline.rs
+// 与历史扫描报告对比，找出新暴露的端点和新的敏感信息
+use super::ScanError;
+use super::scanner::ComprehensiveScanReport;
+use super::vulnerability::SensitiveInfoFinding;
+use serde::{Serialize, Deserialize};
+use std::collections::{HashMap, HashSet};
+use std::fs;
+use std::path::Path;
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct BaselineDiff {
+    pub baseline_timestamp: String,
+    pub new_endpoints: Vec<NewEndpoint>,
+    pub status_changes: Vec<StatusChange>,
+    pub new_findings: Vec<SensitiveInfoFinding>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct NewEndpoint {
+    pub url: String,
+    pub status_code: u16,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct StatusChange {
+    pub url: String,
+    pub old_status: u16,
+    pub new_status: u16,
+}
+
+impl BaselineDiff {
+    pub fn is_empty(&self) -> bool {
+        self.new_endpoints.is_empty() && self.status_changes.is_empty() && self.new_findings.is_empty()
+    }
+}
+
+pub fn load_baseline(path: &Path) -> Result<ComprehensiveScanReport, ScanError> {
+    let content = fs::read_to_string(path)
+        .map_err(|e| ScanError::IOError(format!("无法读取基线报告: {}", e)))?;
+    serde_json::from_str(&content)
+        .map_err(|e| ScanError::ParseError(format!("基线报告格式无效: {}", e)))
+}
+
+// 报告中每个URL的状态码，403只记录在forbidden_urls中
+fn status_by_url(report: &ComprehensiveScanReport) -> HashMap<&str, u16> {
+    let mut statuses: HashMap<&str, u16> = report.forbidden_urls.iter()
+        .map(|url| (url.as_str(), 403))
+        .collect();
+    for result in &report.basic_results {
+        statuses.insert(result.url.as_str(), result.status_code);
+    }
+    statuses
+}
+
+// 以URL为键比较两份报告，只保留新增或变化的内容
+pub fn diff_reports(baseline: &ComprehensiveScanReport, current: &ComprehensiveScanReport) -> BaselineDiff {
+    let old_statuses = status_by_url(baseline);
+    let new_statuses = status_by_url(current);
+
+    let mut diff = BaselineDiff {
+        baseline_timestamp: baseline.scan_timestamp.clone(),
+        ..Default::default()
+    };
+
+    let mut urls: Vec<_> = new_statuses.into_iter().collect();
+    urls.sort();
+    for (url, new_status) in urls {
+        match old_statuses.get(url) {
+            None => diff.new_endpoints.push(NewEndpoint { url: url.to_string(), status_code: new_status }),
+            Some(&old_status) if old_status != new_status => diff.status_changes.push(StatusChange {
+                url: url.to_string(),
+                old_status,
+                new_status,
+            }),
+            _ => {}
+        }
+    }
+
+    // 同一URL上出现了基线中没有的敏感信息类型
+    let old_findings: HashSet<(&str, &str)> = baseline.sensitive_findings.iter()
+        .map(|f| (f.url.as_str(), f.info_type.as_str()))
+        .collect();
+    diff.new_findings = current.sensitive_findings.iter()
+        .filter(|f| !old_findings.contains(&(f.url.as_str(), f.info_type.as_str())))
+        .cloned()
+        .collect();
+
+    diff
+}
@@ -1,15 +1,46 @@
 // config.rs
 use structopt::StructOpt;
-use std::path::PathBuf;
+use structopt::clap::AppSettings;
+use std::ffi::OsString;
+use std::fs;
+use std::net::IpAddr;
+use std::path::{Path, PathBuf};
 use super::ScanError;
+use super::decode::is_known_charset;
+use base64::Engine;
+use base64::engine::general_purpose::{STANDARD, URL_SAFE_NO_PAD};
+use serde_json::Value;
+use log::{info, warn};
+use url::Url;
+
+// 并发数的硬上限，以及超过后给出提示的阈值
+const MAX_CONCURRENCY: usize = 1000;
+const CONCURRENCY_WARN_THRESHOLD: usize = 100;
+
+// 支持的代理协议，socks5h 由代理服务器解析域名
+const PROXY_SCHEMES: [&str; 4] = ["http://", "https://", "socks5://", "socks5h://"];
+
+// --min-tls / --max-tls 可选的TLS版本，按从低到高排列
+pub const TLS_VERSIONS: [&str; 4] = ["1.0", "1.1", "1.2", "1.3"];
+
+// 默认连接超时 (秒)
+const DEFAULT_CONNECT_TIMEOUT: u64 = 5;
 
 #[derive(Debug, StructOpt)]
 pub struct Config {
-    /// 目标 URL (例如: https://api.example.com)
-    #[structopt(short, long)]
-    pub target: String,
+    /// 目标 URL (例如: https://api.example.com)，使用 --absolute-urls 或 --replay 时可省略
+    #[structopt(short, long, required_unless_one = &["absolute-urls", "replay"])]
+    pub target: Option<String>,
+
+    /// 字典中每行都是完整URL，原样请求而不与 --target 拼接
+    #[structopt(long)]
+    pub absolute_urls: bool,
 
-    /// 字典文件路径
+    /// 字典条目与目标URL的拼接方式：path 按路径拼接 (补全'/')，query 作为查询参数追加，raw 原样追加
+    #[structopt(long, default_value = "path", possible_values = &["path", "query", "raw"])]
+    pub join_mode: String,
+
+    /// 字典文件路径 (为 - 时从标准输入读取)
     #[structopt(short, long, default_value = "./config/api_dict.txt")]
     pub dictionary: PathBuf,
 
@@ -17,22 +48,154 @@ pub struct Config {
     #[structopt(short, long, default_value = "./config/scan_report.json")]
     pub output: PathBuf,
 
+    /// 将本次结果合并到已有的JSON报告中 (去重) 而不是覆盖
+    #[structopt(long)]
+    pub output_append: bool,
+
+    /// 流式输出文件 (JSON Lines), 每条结果和发现产生后立即写入, 不在内存中保留
+    #[structopt(long)]
+    pub stream_output: Option<PathBuf>,
+
+    /// 将所有请求和响应记录到HAR文件 (响应体受 --max-body-bytes 限制)
+    #[structopt(long)]
+    pub har: Option<PathBuf>,
+
+    /// 另外写出一个精简的JSON摘要 (路径数、按类型的发现数、5xx和403数量、整体风险、耗时)
+    #[structopt(long)]
+    pub summary_output: Option<PathBuf>,
+
+    /// 不发送请求，对 --har 保存的响应重新运行检测并生成报告 (不需要 --target 和字典)
+    #[structopt(long)]
+    pub replay: Option<PathBuf>,
+
+    /// 报告输出目录, 报告按 scan_<主机>_<时间戳>.json 自动命名 (优先于 --output)
+    #[structopt(long)]
+    pub output_dir: Option<PathBuf>,
+
     /// 并发请求数量
     #[structopt(short, long, default_value = "20")]
     pub concurrency: usize,
 
+    /// 根据响应耗时和错误率自动调整并发数：从4开始逐步增加，变慢或出错时减半，--concurrency 为上限
+    #[structopt(long)]
+    pub auto_concurrency: bool,
+
+    /// 单个主机的最大并发请求数 (不超过 --concurrency 时生效)
+    #[structopt(long)]
+    pub concurrency_per_host: Option<usize>,
+
+    /// 每个请求发送前的基础延迟 (毫秒)
+    #[structopt(long, default_value = "0")]
+    pub delay_ms: u64,
+
+    /// 延迟的随机浮动范围 (毫秒)，实际延迟为 delay ± rand(0..jitter)
+    #[structopt(long, default_value = "0")]
+    pub jitter_ms: u64,
+
+    /// 扫描的最长时间 (秒)，从扫描开始计时，到期后停止派发新请求并保存部分报告
+    #[structopt(long)]
+    pub max_duration: Option<u64>,
+
+    /// 扫描阶段最多发送的请求数 (含HEAD预探测和重试)，达到后停止派发新请求并保存部分报告
+    #[structopt(long)]
+    pub max_requests: Option<usize>,
+
+    /// 探测各主机的DNS、TCP连接、TLS握手和首字节耗时，并记录每个响应体的下载耗时
+    #[structopt(long)]
+    pub detailed_timing: bool,
+
+    /// 扫描前读取每个HTTPS主机的证书 (主题、颁发者、有效期、SAN)，自签名、已过期或30天内过期时报告低风险发现
+    #[structopt(long)]
+    pub tls_info: bool,
+
+    /// 只扫描随机抽取的N个路径，用于在完整扫描前快速估计结果
+    #[structopt(long)]
+    pub sample_count: Option<usize>,
+
+    /// 只扫描随机抽取的百分比路径 (0-100]，与 --sample-count 二选一
+    #[structopt(long)]
+    pub sample_percent: Option<f64>,
+
+    /// 随机数种子，用于复现延迟序列和抽样结果
+    #[structopt(long)]
+    pub seed: Option<u64>,
+
     /// 请求超时时间 (秒)
     #[structopt(long, default_value = "10")]
     pub timeout: u64,
 
-    /// 代理服务器 (例如: http://localhost:8080)
+    /// 建立连接的超时时间 (秒, 默认为5秒与 --timeout 中的较小值)
+    #[structopt(long)]
+    pub connect_timeout: Option<u64>,
+
+    /// 每个主机保留的最大空闲连接数 (默认不限制)
+    #[structopt(long)]
+    pub pool_max_idle_per_host: Option<usize>,
+
+    /// 空闲连接的保留时间 (秒, 默认90)
+    #[structopt(long)]
+    pub pool_idle_timeout: Option<u64>,
+
+    /// 发送 Connection: close 并禁用连接复用 (优先于连接池参数)
+    #[structopt(long)]
+    pub no_keepalive: bool,
+
+    /// 代理服务器 (例如: http://localhost:8080 或 socks5://127.0.0.1:1080)
     #[structopt(long)]
     pub proxy: Option<String>,
 
+    /// 代理认证 (格式 用户名:密码), 需配合 --proxy
+    #[structopt(long)]
+    pub proxy_auth: Option<String>,
+
+    /// 将主机名固定解析到指定IP (格式 主机:IP，可多次指定)
+    #[structopt(long, number_of_values = 1)]
+    pub resolve: Vec<String>,
+
+    /// 覆盖请求的Host头 (主机[:端口])，连接仍发往 --target，用于扫描虚拟主机
+    #[structopt(long)]
+    pub host_header: Option<String>,
+
     /// 认证令牌 (Bearer 令牌)
     #[structopt(long)]
     pub auth_token: Option<String>,
 
+    /// 从环境变量读取认证令牌，避免令牌出现在shell历史和进程列表中
+    #[structopt(long)]
+    pub auth_token_env: Option<String>,
+
+    /// 从文件读取认证令牌 (去除首尾空白)
+    #[structopt(long)]
+    pub auth_token_file: Option<PathBuf>,
+
+    /// 按主机指定认证令牌的文件，每行 URL,令牌 (如 https://a.com,TOKEN_A)，对该主机的请求覆盖 --auth-token
+    #[structopt(long)]
+    pub host_tokens: Option<PathBuf>,
+
+    // 由 resolve_auth_token 从 --host-tokens 读取的 (源, 令牌)，源为 https://a.com 形式
+    #[structopt(skip)]
+    pub host_auth_tokens: Vec<(String, String)>,
+
+    /// 令牌的认证方案，如 Bearer、Token；为空字符串时直接发送令牌
+    #[structopt(long, default_value = "Bearer")]
+    pub auth_scheme: String,
+
+    /// 携带令牌的请求头名称，如 X-Api-Key
+    #[structopt(long, default_value = "Authorization")]
+    pub auth_header_name: String,
+
+    /// 收到401时执行的令牌刷新命令，其标准输出作为新的令牌并重试请求
+    #[structopt(long)]
+    pub token_refresh_cmd: Option<String>,
+
+    /// 对带认证返回200的路径再发送一次不带认证的请求，同样返回200时报告 PossibleAuthBypass
+    #[structopt(long)]
+    pub auth_bypass_check: bool,
+
+    /// HTTP Basic认证 (格式: 用户名:密码), 与 --auth-token 互斥
+    #[structopt(long)]
+    pub basic_auth: Option<String>,
+
     /// User-Agent列表文件路径
     #[structopt(
         long,
@@ -41,6 +204,29 @@ pub struct Config {
     )]
     pub user_agent_file: PathBuf,
 
+    /// 所有请求固定使用该User-Agent, 不读取UA文件也不逐个验证 (--ua-cache 不生效)
+    #[structopt(long)]
+    pub user_agent: Option<String>,
+
+    /// UA缓存文件：启动时优先尝试上次验证成功的UA，验证出新UA后更新
+    #[structopt(long)]
+    pub ua_cache: Option<PathBuf>,
+
+    /// 请求的Accept头
+    #[structopt(
+        long,
+        default_value = "text/html,application/xhtml+xml,application/xml;q=0.9,image/avif,image/webp,*/*;q=0.8"
+    )]
+    pub accept: String,
+
+    /// 请求的Accept-Language头
+    #[structopt(long, default_value = "zh-CN,zh;q=0.9,en;q=0.8")]
+    pub accept_language: String,
+
+    /// 拼接在目标URL和每个路径之间的基础路径 (如 /service/v2)
+    #[structopt(long)]
+    pub base_path: Option<String>,
+
     /// 包含路径的文件 (每行一个路径)
     #[structopt(long)]
     pub include_paths: Option<PathBuf>,
@@ -48,26 +234,604 @@ pub struct Config {
     /// 排除路径的文件 (每行一个路径)
     #[structopt(long)]
     pub exclude_paths: Option<PathBuf>,
+
+    /// 只扫描完整URL匹配该正则的路径
+    #[structopt(long)]
+    pub include_regex: Option<String>,
+
+    /// 跳过完整URL匹配该正则的路径 (优先于 --include-regex)
+    #[structopt(long)]
+    pub exclude_regex: Option<String>,
+
+    /// 查询参数载荷文件 (每行一个, 如 debug=true), 每个路径会额外附加每个载荷请求一次
+    #[structopt(long)]
+    pub query_payloads: Option<PathBuf>,
+
+    /// 风险评分覆盖文件 (每行 "类型 = 评分"), 按info_type替换发现的默认风险评分
+    #[structopt(long)]
+    pub risk_overrides: Option<PathBuf>,
+
+    /// 误报抑制列表 (每行一个字面值, 以 re: 开头的按正则处理), 匹配的发现会被丢弃
+    #[structopt(long)]
+    pub ignore_findings: Option<PathBuf>,
+
+    /// 为字典中的每个路径追加扩展名变体 (逗号分隔, 如 json,bak,old), 原路径保留
+    #[structopt(long, use_delimiter = true)]
+    pub extensions: Vec<String>,
+
+    /// 已经带扩展名的路径也追加 --extensions (默认跳过, 如 /config.php 不会变成 /config.php.bak)
+    #[structopt(long)]
+    pub extensions_all: bool,
+
+    /// 模糊测试字典, 路径中的 FUZZ 标记会被替换为字典中的每个单词
+    #[structopt(long)]
+    pub fuzz_wordlist: Option<PathBuf>,
+
+    /// OpenAPI/Swagger JSON规范文件, 其中的路径会合并到扫描列表
+    #[structopt(long)]
+    pub openapi: Option<PathBuf>,
+
+    /// 响应体与之前扫描过的完全相同时跳过敏感信息检测 (响应头仍会检测)
+    #[structopt(long)]
+    pub skip_duplicate_bodies: bool,
+
+    /// 扫描前读取目标主机的 /sitemap.xml (含sitemap索引和gzip压缩的sitemap)，把其中的路径合并到扫描列表
+    #[structopt(long)]
+    pub follow_sitemap: bool,
+
+    /// 扫描前发送一个正常请求和一个带攻击特征的请求，判断目标是否有WAF拦截
+    #[structopt(long)]
+    pub waf_check: bool,
+
+    /// 跳过扫描前对目标的连通性预检 (默认在加载字典和验证UA之前先请求一次目标，连接失败时立即退出)
+    #[structopt(long)]
+    pub skip_preflight: bool,
+
+    /// OpenAPI路径模板参数 (如 /users/{id}) 没有示例值时使用的占位值
+    #[structopt(long, default_value = "1")]
+    pub openapi_placeholder: String,
+
+    /// 基线报告 (之前生成的JSON报告), 扫描后只输出新增或变化的内容
+    #[structopt(long)]
+    pub baseline: Option<PathBuf>,
+
+    /// 摘要中列出的最慢端点数量
+    #[structopt(long, default_value = "5")]
+    pub slowest: usize,
+
+    /// 整体风险评分中最高发现风险评分的权重
+    #[structopt(long, default_value = "6")]
+    pub risk_weight_max: f64,
+
+    /// 整体风险评分中每个去重敏感值 (风险评分/10) 的权重
+    #[structopt(long, default_value = "1")]
+    pub risk_weight_secret: f64,
+
+    /// 整体风险评分中每个5xx响应的权重
+    #[structopt(long, default_value = "0.5")]
+    pub risk_weight_5xx: f64,
+
+    /// 整体风险评分中每个403响应的权重
+    #[structopt(long, default_value = "0.2")]
+    pub risk_weight_403: f64,
+
+    /// 只保留风险评分不低于该值的发现 (0为全部保留)
+    #[structopt(long, default_value = "0")]
+    pub min_risk: u8,
+
+    /// 存在风险评分不低于该值的发现时以退出码2结束 (用于CI门禁)
+    #[structopt(long)]
+    pub fail_on: Option<u8>,
+
+    /// 扫描结束后将发现以JSON POST到该URL，失败时最多尝试3次
+    #[structopt(long)]
+    pub webhook: Option<String>,
+
+    /// 只推送风险评分不低于该值的发现，没有达到阈值的发现时不发送
+    #[structopt(long, default_value = "0")]
+    pub webhook_min_risk: u8,
+
+    /// 报告格式 (json、md 或 junit)
+    #[structopt(long, default_value = "json", possible_values = &["json", "md", "junit"])]
+    pub format: String,
+
+    /// JWT令牌存在问题(alg=none、已过期、即将过期)时直接报错而不是警告
+    #[structopt(long)]
+    pub strict_token: bool,
+
+    /// JWT令牌剩余有效期低于该值(秒)时给出提示
+    #[structopt(long, default_value = "300")]
+    pub token_expiry_warn: i64,
+
+    /// 对非文本响应(图片、PDF等)也进行敏感信息检测
+    #[structopt(long)]
+    pub scan_binary: bool,
+
+    /// 读取响应体的最大字节数，超出部分丢弃 (压缩响应按解压前后分别限制)
+    #[structopt(long, default_value = "5242880")]
+    pub max_body_bytes: usize,
+
+    /// 强制使用的响应体字符集 (如 gbk、iso-8859-1)，默认按BOM和Content-Type检测
+    #[structopt(long)]
+    pub charset: Option<String>,
+
+    /// 随请求发送Origin头并检查CORS响应头，反射Origin或通配符加凭据时报告 PermissiveCORS
+    #[structopt(long)]
+    pub check_cors: bool,
+
+    /// 每个路径额外发送一次带唯一令牌查询参数的请求，令牌及HTML特殊字符未编码地出现在响应中时报告 ReflectedInput
+    #[structopt(long)]
+    pub reflect_canary: bool,
+
+    /// 检测自动生成的目录列表页面 (如 "Index of /")，发现时报告 DirectoryListing
+    #[structopt(long)]
+    pub check_listing: bool,
+
+    /// 收到429时按Retry-After等待后重试同一路径 (最多3次，每次最多等待60秒)
+    #[structopt(long)]
+    pub respect_retry_after: bool,
+
+    /// 熔断阈值 (0-1)：最近20个请求中5xx和请求失败的比例达到该值时暂停派发请求
+    #[structopt(long)]
+    pub error_threshold: Option<f64>,
+
+    /// 熔断后的暂停时间 (秒)，需配合 --error-threshold 使用
+    #[structopt(long, default_value = "30")]
+    pub error_cooldown: u64,
+
+    /// 每个路径依次使用的HTTP方法 (逗号分隔)，如 GET,OPTIONS,POST；请求数按方法数成倍增加
+    #[structopt(long, use_delimiter = true, default_value = "GET")]
+    pub methods: Vec<String>,
+
+    /// 先发送HEAD请求，只有状态码和Content-Type值得检测时才发送GET
+    #[structopt(long)]
+    pub head_first: bool,
+
+    /// 需要进行敏感信息检测的Content-Type列表 (逗号分隔, 支持 text/* 和 *+json 形式)
+    #[structopt(
+        long,
+        use_delimiter = true,
+        default_value = "text/*,application/json,application/xml,application/javascript,application/x-www-form-urlencoded,*+json,*+xml"
+    )]
+    pub content_types: Vec<String>,
+
+    /// 限定规则只对指定Content-Type的响应体生效, 格式 规则名=类型1|类型2 (逗号分隔多条, 类型写法同 --content-types)
+    #[structopt(long, use_delimiter = true, default_value = "sensitive_json_key=application/json|*+json")]
+    pub rule_content_types: Vec<String>,
+
+    /// 发现中保留的匹配前后上下文字节数
+    #[structopt(long, default_value = "30")]
+    pub context_bytes: usize,
+
+    /// 只探测端点是否存在：记录状态码和响应头中的长度，不读取响应体也不做任何检测
+    #[structopt(long)]
+    pub paths_only: bool,
+
+    /// 报告中的结果和发现按URL排序 (默认按请求完成顺序)，便于diff两次扫描的报告
+    #[structopt(long)]
+    pub sort: bool,
+
+    /// 扫描结束后在终端以对齐表格逐条打印发现 (按风险评分降序)
+    #[structopt(long)]
+    pub table: bool,
+
+    /// 在报告的去重敏感值列表中显示完整值 (仅用于本地排查)
+    #[structopt(long)]
+    pub show_secrets: bool,
+
+    /// 脱敏输出：所有输出格式中的匹配值均遮盖为 sk-live-****abcd 形式，上下文中的值替换为 [REDACTED]
+    #[structopt(long)]
+    pub redact: bool,
+
+    /// JSON响应中视为敏感的键名 (逗号分隔, 忽略大小写及 _ 和 -)
+    #[structopt(
+        long,
+        use_delimiter = true,
+        default_value = "password,passwd,pwd,secret,client_secret,access_token,refresh_token,id_token,token,api_key,private_key,credential"
+    )]
+    pub sensitive_keys: Vec<String>,
+
+    /// 在结果中保存响应头
+    #[structopt(long)]
+    pub capture_headers: bool,
+
+    /// 只保存这些响应头 (逗号分隔, 默认保存全部), 需配合 --capture-headers
+    #[structopt(long, use_delimiter = true)]
+    pub capture_header_names: Vec<String>,
+
+    /// 跳过TLS证书验证 (仅用于自签名证书的内部主机, 存在中间人风险)
+    #[structopt(long)]
+    pub insecure: bool,
+
+    /// 允许的最低TLS版本 (1.0、1.1、1.2 或 1.3)
+    #[structopt(long, possible_values = &TLS_VERSIONS)]
+    pub min_tls: Option<String>,
+
+    /// 允许的最高TLS版本 (1.0、1.1、1.2 或 1.3)，用于测试旧版本端点
+    #[structopt(long, possible_values = &TLS_VERSIONS)]
+    pub max_tls: Option<String>,
+
+    /// 日志详细程度 (-v 输出每个路径的结果, -vv 输出请求/响应元数据)
+    #[structopt(short, long, parse(from_occurrences))]
+    pub verbose: u8,
+
+    /// 安静模式：不显示进度条和摘要，stdout无输出，stderr只输出错误
+    #[structopt(short, long)]
+    pub quiet: bool,
+
+    /// 不显示进度条 (stderr不是终端时自动隐藏)
+    #[structopt(long)]
+    pub no_progress: bool,
+
+    /// 配置文件路径 (.toml、.yaml 或 .yml)，键为长参数名，命令行参数优先于文件中的值
+    #[structopt(long)]
+    pub config_file: Option<PathBuf>,
+}
+
+// 读取 --host-tokens 文件：每行 URL,令牌，空行和 # 开头的行忽略；URL只取协议、主机和端口，同一主机只能出现一次
+// 错误信息中只显示行号和URL，不显示令牌
+fn load_host_tokens(path: &Path) -> Result<Vec<(String, String)>, ScanError> {
+    let content = fs::read_to_string(path)
+        .map_err(|e| ScanError::IOError(format!("无法读取主机令牌文件 {}: {}", path.display(), e)))?;
+    let mut tokens: Vec<(String, String)> = Vec::new();
+    for (index, line) in content.lines().enumerate() {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+        let (target, token) = line.split_once(',')
+            .map(|(target, token)| (target.trim(), token.trim()))
+            .filter(|(_, token)| !token.is_empty())
+            .ok_or_else(|| ScanError::InvalidConfig(format!("主机令牌文件第 {} 行格式应为 URL,令牌", index + 1)))?;
+        let url = Url::parse(target)
+            .ok()
+            .filter(|url| matches!(url.scheme(), "http" | "https") && url.host_str().is_some_and(|host| !host.is_empty()))
+            .ok_or_else(|| ScanError::InvalidConfig(format!("主机令牌文件第 {} 行的URL无效，需以http://或https://开头: {}", index + 1, target)))?;
+        let origin = url.origin().ascii_serialization();
+        if tokens.iter().any(|(existing, _)| *existing == origin) {
+            return Err(ScanError::InvalidConfig(format!("主机令牌文件第 {} 行重复指定了 {}", index + 1, origin)));
+        }
+        tokens.push((origin, token.to_string()));
+    }
+    Ok(tokens)
+}
+
+// 解析目标URL，要求http/https协议、包含主机名且不带查询参数或片段
+pub fn parse_target(target: &str) -> Result<Url, ScanError> {
+    let url = Url::parse(target.trim()).map_err(|e| {
+        // 裸IP或 主机:端口 会被解析为缺少协议或未知协议，给出更明确的提示
+        if !target.contains("://") {
+            ScanError::InvalidConfig(format!("目标URL缺少协议，请写成 http://{} 的形式", target.trim()))
+        } else {
+            ScanError::InvalidConfig(format!("请输入正确的URL ({}): {}", target, e))
+        }
+    })?;
+
+    if url.scheme() != "http" && url.scheme() != "https" {
+        if !target.contains("://") {
+            return Err(ScanError::InvalidConfig(format!("目标URL缺少协议，请写成 http://{} 的形式", target.trim())));
+        }
+        return Err(ScanError::InvalidConfig(format!("目标URL必须以http://或https://开头: {}", target)));
+    }
+    if url.host_str().is_none_or(str::is_empty) {
+        return Err(ScanError::InvalidConfig(format!("目标URL缺少主机名: {}", target)));
+    }
+    if url.query().is_some() || url.fragment().is_some() {
+        return Err(ScanError::InvalidConfig(format!("目标URL不能包含查询参数或片段: {}", target)));
+    }
+
+    Ok(url)
+}
+
+// 校验 --absolute-urls 字典中的一行，返回规范化后的URL
+pub fn parse_absolute_url(entry: &str) -> Result<String, ScanError> {
+    let url = Url::parse(entry.trim())
+        .map_err(|e| ScanError::InvalidConfig(format!("字典中的URL无效 ({}): {}", entry, e)))?;
+    if url.scheme() != "http" && url.scheme() != "https" {
+        return Err(ScanError::InvalidConfig(format!("字典中的URL必须以http://或https://开头: {}", entry)));
+    }
+    if url.host_str().is_none_or(str::is_empty) {
+        return Err(ScanError::InvalidConfig(format!("字典中的URL缺少主机名: {}", entry)));
+    }
+    Ok(url.to_string())
+}
+
+// 解码JWT的头部和载荷 (base64url)
+pub fn decode_jwt(token: &str) -> Result<(Value, Value), ScanError> {
+    let segments: Vec<&str> = token.split('.').collect();
+    if segments.len() != 3 {
+        return Err(ScanError::ParseError("JWT令牌必须由三段组成".to_string()));
+    }
+
+    let decode_segment = |name: &str, segment: &str| -> Result<Value, ScanError> {
+        let bytes = URL_SAFE_NO_PAD
+            .decode(segment.trim_end_matches('='))
+            .map_err(|e| ScanError::ParseError(format!("JWT{}base64解码失败: {}", name, e)))?;
+        serde_json::from_slice(&bytes)
+            .map_err(|e| ScanError::ParseError(format!("JWT{}不是有效的JSON: {}", name, e)))
+    };
+
+    let header = decode_segment("头部", segments[0])?;
+    let claims = decode_segment("载荷", segments[1])?;
+    Ok((header, claims))
+}
+
+// 将基础路径拼接到目标URL后，忽略多余的斜杠：/api/、api、//api// 都得到 {target}/api
+pub fn join_base_path(target: &str, base_path: &str) -> String {
+    let segments: Vec<&str> = base_path.split('/').filter(|s| !s.is_empty()).collect();
+    let target = target.trim_end_matches('/');
+    if segments.is_empty() {
+        target.to_string()
+    } else {
+        format!("{}/{}", target, segments.join("/"))
+    }
+}
+
+// 从命令行参数中找出 --config-file 的值
+fn find_config_file(args: &[OsString]) -> Option<PathBuf> {
+    let mut iter = args.iter().skip(1);
+    while let Some(arg) = iter.next() {
+        let arg = arg.to_string_lossy();
+        if arg == "--config-file" {
+            return iter.next().map(PathBuf::from);
+        }
+        if let Some(path) = arg.strip_prefix("--config-file=") {
+            return Some(PathBuf::from(path));
+        }
+    }
+    None
+}
+
+// 配置文件中的单个值转换为参数值，只接受字符串、数字和布尔值
+fn config_value_to_arg(key: &str, value: &Value) -> Result<String, ScanError> {
+    match value {
+        Value::String(s) => Ok(s.clone()),
+        Value::Number(n) => Ok(n.to_string()),
+        Value::Bool(b) => Ok(b.to_string()),
+        _ => Err(ScanError::InvalidConfig(format!("配置文件中 {} 的值必须是字符串、数字或布尔值", key))),
+    }
+}
+
+// 读取TOML/YAML配置文件，把每个键转换为对应的长参数:
+// true 的布尔值转换为开关，false 和空值忽略，数组按元素重复该参数，verbose 的数字表示 -v 的次数
+fn config_file_args(path: &Path) -> Result<Vec<OsString>, ScanError> {
+    let content = fs::read_to_string(path)
+        .map_err(|e| ScanError::IOError(format!("无法读取配置文件 {:?}: {}", path, e)))?;
+    let extension = path.extension()
+        .and_then(|e| e.to_str())
+        .map(|e| e.to_ascii_lowercase());
+    let table: serde_json::Map<String, Value> = match extension.as_deref() {
+        Some("toml") => toml::from_str(&content)
+            .map_err(|e| ScanError::ParseError(format!("配置文件格式错误: {}", e)))?,
+        Some("yaml") | Some("yml") => serde_yaml::from_str::<Option<_>>(&content)
+            .map_err(|e| ScanError::ParseError(format!("配置文件格式错误: {}", e)))?
+            .unwrap_or_default(),
+        _ => return Err(ScanError::InvalidConfig("配置文件扩展名必须是 .toml、.yaml 或 .yml".to_string())),
+    };
+
+    let mut args = Vec::new();
+    for (key, value) in &table {
+        let flag = format!("--{}", key.replace('_', "-"));
+        match value {
+            _ if flag == "--config-file" => {
+                return Err(ScanError::InvalidConfig("配置文件中不能再指定 config-file".to_string()));
+            }
+            Value::Bool(true) => args.push(flag.into()),
+            Value::Bool(false) | Value::Null => {}
+            Value::Number(n) if flag == "--verbose" => {
+                let count = n.as_u64().unwrap_or(0);
+                args.extend((0..count).map(|_| OsString::from("--verbose")));
+            }
+            Value::Array(items) => {
+                for item in items {
+                    args.push(format!("{}={}", flag, config_value_to_arg(key, item)?).into());
+                }
+            }
+            other => args.push(format!("{}={}", flag, config_value_to_arg(key, other)?).into()),
+        }
+    }
+    Ok(args)
+}
+
+// 解析 --resolve 的 主机:IP，IPv6地址可以带方括号
+pub fn parse_resolve(entry: &str) -> Result<(String, IpAddr), ScanError> {
+    let invalid = || ScanError::InvalidConfig(format!("--resolve 格式应为 主机:IP: {}", entry));
+    let (host, ip) = entry.split_once(':').ok_or_else(invalid)?;
+    let host = host.trim();
+    if host.is_empty() {
+        return Err(invalid());
+    }
+    let ip = ip.trim().trim_start_matches('[').trim_end_matches(']');
+    let ip = ip.parse::<IpAddr>().map_err(|_| invalid())?;
+    Ok((host.to_ascii_lowercase(), ip))
 }
 
 impl Config {
+    // 解析命令行参数，指定了 --config-file 时合并配置文件中的值
+    pub fn from_args_with_config_file() -> Result<Config, ScanError> {
+        Self::from_iter_with_config_file(std::env::args_os())
+    }
+
+    // 配置文件中的参数插在命令行参数之前，并让所有参数可以覆盖自身，
+    // 因此优先级为 命令行 > 配置文件 > 默认值；可重复的参数 (如 --resolve) 两边的值会合并
+    pub fn from_iter_with_config_file<I>(iter: I) -> Result<Config, ScanError>
+    where
+        I: IntoIterator,
+        I::Item: Into<OsString>,
+    {
+        let mut args: Vec<OsString> = iter.into_iter().map(Into::into).collect();
+        if let Some(path) = find_config_file(&args) {
+            let file_args = config_file_args(&path)?;
+            let split = args.len().min(1);
+            args.splice(split..split, file_args);
+        }
+        let matches = Config::clap()
+            .setting(AppSettings::AllArgsOverrideSelf)
+            .get_matches_from(args);
+        let mut config = Config::from_clap(&matches);
+        config.resolve_auth_token()?;
+        Ok(config)
+    }
+
+    // 读取 --auth-token-env 或 --auth-token-file 指定的令牌并写入 auth_token，
+    // 三种令牌来源只能指定一个；同时读取 --host-tokens 文件。读取后清空来源，重复调用没有影响
+    pub fn resolve_auth_token(&mut self) -> Result<(), ScanError> {
+        let sources = [self.auth_token.is_some(), self.auth_token_env.is_some(), self.auth_token_file.is_some()];
+        if sources.iter().filter(|&&set| set).count() > 1 {
+            return Err(ScanError::InvalidConfig("--auth-token、--auth-token-env 和 --auth-token-file 只能指定一个。".to_string()));
+        }
+        if let Some(name) = self.auth_token_env.take() {
+            let token = std::env::var(&name)
+                .map_err(|_| ScanError::InvalidConfig(format!("环境变量 {} 未设置或不是有效的UTF-8。", name)))?;
+            self.auth_token = Some(token.trim().to_string());
+        } else if let Some(path) = self.auth_token_file.take() {
+            let token = fs::read_to_string(&path)
+                .map_err(|e| ScanError::IOError(format!("无法读取令牌文件 {}: {}", path.display(), e)))?;
+            self.auth_token = Some(token.trim().to_string());
+        }
+        if let Some(path) = self.host_tokens.take() {
+            self.host_auth_tokens = load_host_tokens(&path)?;
+            info!("已从 {} 读取 {} 个主机的认证令牌", path.display(), self.host_auth_tokens.len());
+        }
+        Ok(())
+    }
+
+    // 连接超时，未指定时取5秒与总超时中的较小值
+    pub fn effective_connect_timeout(&self) -> u64 {
+        self.connect_timeout.unwrap_or(DEFAULT_CONNECT_TIMEOUT.min(self.timeout))
+    }
+
+    // 字典为 - 时从标准输入读取路径
+    pub fn dictionary_from_stdin(&self) -> bool {
+        self.dictionary.as_os_str() == "-"
+    }
+
     pub fn validate(&self) -> Result<(), ScanError> {
         // 目标url格式验证
-        if !self.target.starts_with("http://") && !self.target.starts_with("https://") {
-            return Err(ScanError::InvalidConfig("请输入正确的URL".to_string()));
+        match &self.target {
+            Some(target) => {
+                parse_target(target)?;
+            }
+            None if self.absolute_urls || self.replay.is_some() => {}
+            None => return Err(ScanError::InvalidConfig("必须指定 --target。".to_string())),
+        }
+        if let Some(replay) = &self.replay {
+            if !replay.exists() {
+                return Err(ScanError::InvalidConfig("--replay 指定的HAR文件不存在。".to_string()));
+            }
+            if self.har.is_some() || self.stream_output.is_some() {
+                return Err(ScanError::InvalidConfig("--replay 不能与 --har 或 --stream-output 同时使用。".to_string()));
+            }
+        }
+        if self.quiet && self.verbose > 0 {
+            return Err(ScanError::InvalidConfig("--quiet 不能与 -v 同时使用。".to_string()));
+        }
+        if self.absolute_urls && (self.openapi.is_some() || self.base_path.is_some() || self.follow_sitemap || self.waf_check) {
+            return Err(ScanError::InvalidConfig("--absolute-urls 不能与 --openapi、--base-path、--follow-sitemap 或 --waf-check 同时使用。".to_string()));
+        }
+        if self.join_mode != "path" && self.absolute_urls {
+            return Err(ScanError::InvalidConfig("--join-mode 不能与 --absolute-urls 同时使用。".to_string()));
+        }
+        if self.join_mode == "query" && (self.openapi.is_some() || self.follow_sitemap) {
+            return Err(ScanError::InvalidConfig("--join-mode query 不能与 --openapi 或 --follow-sitemap 同时使用。".to_string()));
         }
         
         // 验证字典路径存在
-        if !self.dictionary.exists() {
+        if self.replay.is_none() && !self.dictionary_from_stdin() && !self.dictionary.exists() {
             return Err(ScanError::InvalidConfig("字典文件不存在。".to_string()));
         }
         
-        // 验证并发合理性
-        if self.concurrency == 0 || self.concurrency > 100 {
-            return Err(ScanError::InvalidConfig("并发数区间为1~100。".to_string()));
+        if self.extensions.iter().any(|ext| {
+            let ext = ext.trim().trim_start_matches('.');
+            ext.is_empty() || ext.contains(['/', '?', '#']) || ext.contains(char::is_whitespace)
+        }) {
+            return Err(ScanError::InvalidConfig("--extensions 中的扩展名不能为空，也不能包含 / ? # 或空白字符。".to_string()));
+        }
+        if self.extensions_all && self.extensions.is_empty() {
+            return Err(ScanError::InvalidConfig("--extensions-all 需要同时指定 --extensions。".to_string()));
+        }
+        
+        // 验证模糊测试字典存在
+        if let Some(wordlist) = &self.fuzz_wordlist
+            && !wordlist.exists()
+        {
+            return Err(ScanError::InvalidConfig("模糊测试字典不存在。".to_string()));
+        }
+        
+        // 验证查询参数载荷文件存在
+        if let Some(payloads) = &self.query_payloads
+            && !payloads.exists()
+        {
+            return Err(ScanError::InvalidConfig("查询参数载荷文件不存在。".to_string()));
+        }
+        
+        // 验证误报抑制列表存在
+        if let Some(ignore) = &self.ignore_findings
+            && !ignore.exists()
+        {
+            return Err(ScanError::InvalidConfig("误报抑制列表不存在。".to_string()));
+        }
+        
+        // 验证OpenAPI文件存在
+        if let Some(openapi) = &self.openapi
+            && !openapi.exists()
+        {
+            return Err(ScanError::InvalidConfig("OpenAPI文件不存在。".to_string()));
+        }
+        
+        // 验证基线报告存在
+        if let Some(baseline) = &self.baseline
+            && !baseline.exists()
+        {
+            return Err(ScanError::InvalidConfig("基线报告不存在。".to_string()));
+        }
+        
+        // 验证并发合理性：buffer_unordered 同时持有的请求数即并发数，每个请求至少占用一个socket
+        if self.concurrency == 0 || self.concurrency > MAX_CONCURRENCY {
+            return Err(ScanError::InvalidConfig(format!(
+                "并发数区间为1~{}。每个并发请求至少占用一个文件描述符，过高的并发会耗尽系统限制(ulimit -n)。",
+                MAX_CONCURRENCY
+            )));
+        }
+        if self.concurrency > CONCURRENCY_WARN_THRESHOLD {
+            warn!(
+                "并发数 {} 超过 {}：请确认文件描述符上限(ulimit -n)大于并发数，并注意目标服务器的承受能力",
+                self.concurrency, CONCURRENCY_WARN_THRESHOLD
+            );
+        }
+        
+        if let Some(charset) = &self.charset
+            && !is_known_charset(charset)
+        {
+            return Err(ScanError::InvalidConfig(format!("不支持的字符集: {}", charset)));
+        }
+        
+        if let Some(connect_timeout) = self.connect_timeout
+            && connect_timeout > self.timeout
+        {
+            return Err(ScanError::InvalidConfig(format!(
+                "连接超时({}秒)不能大于请求总超时({}秒)。", connect_timeout, self.timeout
+            )));
+        }
+        
+        if self.max_body_bytes == 0 {
+            return Err(ScanError::InvalidConfig("响应体大小上限必须大于0。".to_string()));
+        }
+        
+        if self.concurrency_per_host == Some(0) {
+            return Err(ScanError::InvalidConfig("单主机并发数必须大于0。".to_string()));
         }
         
         // 验证令牌
+        // 令牌来源需先由 resolve_auth_token 读取
+        if self.auth_token_env.is_some() || self.auth_token_file.is_some() || self.host_tokens.is_some() {
+            return Err(ScanError::InvalidConfig("--auth-token-env/--auth-token-file/--host-tokens 尚未读取，请先调用 Config::resolve_auth_token。".to_string()));
+        }
+        for (origin, token) in &self.host_auth_tokens {
+            if reqwest::header::HeaderValue::from_str(&self.token_header_value(token)).is_err() {
+                return Err(ScanError::InvalidConfig(format!("{} 的认证令牌包含不能用于请求头的字符。", origin)));
+            }
+        }
         if let Some(token) = &self.auth_token {
             if token.trim().is_empty() {
                 return Err(ScanError::InvalidConfig("认证令牌不能为空。".to_string()));
@@ -77,24 +841,399 @@ impl Config {
                 if parse.len() != 3 {
                     return Err(ScanError::InvalidConfig("JWT令牌格式无效。".to_string()));
                 }
+                self.inspect_jwt(token)?;
+            }
+        }
+        
+        // 验证Basic认证
+        if let Some(basic_auth) = &self.basic_auth {
+            if self.auth_token.is_some() {
+                return Err(ScanError::InvalidConfig("--basic-auth 与 --auth-token 不能同时使用。".to_string()));
+            }
+            match basic_auth.split_once(':') {
+                Some((user, _)) if !user.is_empty() => {}
+                _ => return Err(ScanError::InvalidConfig("Basic认证格式应为 用户名:密码。".to_string())),
+            }
+        }
+        
+        // 验证Host头：只能是主机名或IP（IPv6需带方括号），可带端口
+        if let Some(host) = &self.host_header {
+            let valid = !host.contains(['/', '?', '#', '@'])
+                && Url::parse(&format!("http://{}", host))
+                    .is_ok_and(|url| url.host_str().is_some_and(|h| !h.is_empty()));
+            if !valid {
+                return Err(ScanError::InvalidConfig(format!("--host-header 格式应为 主机[:端口]，IPv6地址需加方括号: {}", host)));
+            }
+        }
+        
+        // 脱敏模式下不能输出任何原始值
+        if self.redact && self.show_secrets {
+            return Err(ScanError::InvalidConfig("--redact 和 --show-secrets 不能同时使用。".to_string()));
+        }
+        if self.redact && self.har.is_some() {
+            return Err(ScanError::InvalidConfig("--har 会记录原始响应内容，不能与 --redact 同时使用。".to_string()));
+        }
+        
+        // 验证HTTP方法
+        if self.methods.is_empty() {
+            return Err(ScanError::InvalidConfig("--methods 不能为空。".to_string()));
+        }
+        for method in &self.methods {
+            if reqwest::Method::from_bytes(method.trim().to_ascii_uppercase().as_bytes()).is_err() {
+                return Err(ScanError::InvalidConfig(format!("--methods 中的HTTP方法无效: {}", method)));
+            }
+        }
+        
+        if let Some(threshold) = self.error_threshold
+            && !(threshold > 0.0 && threshold <= 1.0)
+        {
+            return Err(ScanError::InvalidConfig(format!("--error-threshold 必须在0到1之间 (不含0): {}", threshold)));
+        }
+        if self.error_cooldown == 0 {
+            return Err(ScanError::InvalidConfig("--error-cooldown 必须大于0。".to_string()));
+        }
+        
+        if self.max_duration == Some(0) {
+            return Err(ScanError::InvalidConfig("--max-duration 必须大于0。".to_string()));
+        }
+        if self.max_requests == Some(0) {
+            return Err(ScanError::InvalidConfig("--max-requests 必须大于0。".to_string()));
+        }
+        
+        // 验证抽样参数
+        if self.sample_count.is_some() && self.sample_percent.is_some() {
+            return Err(ScanError::InvalidConfig("--sample-count 和 --sample-percent 只能指定一个。".to_string()));
+        }
+        if self.sample_count == Some(0) {
+            return Err(ScanError::InvalidConfig("--sample-count 必须大于0。".to_string()));
+        }
+        if let Some(percent) = self.sample_percent
+            && !(percent > 0.0 && percent <= 100.0)
+        {
+            return Err(ScanError::InvalidConfig("--sample-percent 必须在0到100之间 (不含0)。".to_string()));
+        }
+        
+        // 验证风险评分覆盖文件存在
+        if let Some(path) = &self.risk_overrides
+            && !path.exists()
+        {
+            return Err(ScanError::InvalidConfig("风险评分覆盖文件不存在。".to_string()));
+        }
+        
+        // 验证webhook地址
+        if let Some(webhook) = &self.webhook
+            && !Url::parse(webhook).is_ok_and(|url| url.scheme() == "http" || url.scheme() == "https")
+        {
+            return Err(ScanError::InvalidConfig(format!("webhook地址必须是http或https URL: {}", webhook)));
+        }
+        
+        // 验证令牌请求头名称
+        if reqwest::header::HeaderName::from_bytes(self.auth_header_name.as_bytes()).is_err() {
+            return Err(ScanError::InvalidConfig(format!("无效的请求头名称: {}", self.auth_header_name)));
+        }
+        if self.basic_auth.is_some() && !self.auth_header_name.eq_ignore_ascii_case("authorization") {
+            return Err(ScanError::InvalidConfig("--auth-header-name 只适用于 --auth-token。".to_string()));
+        }
+        
+        if self.auth_bypass_check && self.authorization_header().is_none() {
+            return Err(ScanError::InvalidConfig("--auth-bypass-check 需要同时指定 --auth-token 或 --basic-auth。".to_string()));
+        }
+        
+        // 验证令牌刷新命令
+        if let Some(command) = &self.token_refresh_cmd {
+            if command.trim().is_empty() {
+                return Err(ScanError::InvalidConfig("令牌刷新命令不能为空。".to_string()));
+            }
+            if self.basic_auth.is_some() {
+                return Err(ScanError::InvalidConfig("--token-refresh-cmd 不能与 --basic-auth 同时使用。".to_string()));
             }
         }
         
         // 验证代理
-        if let Some(proxy) = &self.proxy {
-            if !proxy.starts_with("http://") && !proxy.starts_with("https://") {
-                return Err(ScanError::InvalidConfig("代理URL必须以http://或https://开头".to_string()));
+        if let Some(proxy) = &self.proxy
+            && !PROXY_SCHEMES.iter().any(|scheme| proxy.starts_with(scheme))
+        {
+            return Err(ScanError::InvalidConfig(
+                "代理URL必须以http://、https://、socks5://或socks5h://开头".to_string()
+            ));
+        }
+        
+        // 追加模式只适用于写入固定路径的JSON报告
+        if self.output_append {
+            if self.format != "json" {
+                return Err(ScanError::InvalidConfig("--output-append 只支持json格式。".to_string()));
+            }
+            if self.output_dir.is_some() || self.stream_output.is_some() {
+                return Err(ScanError::InvalidConfig("--output-append 不能与 --output-dir 或 --stream-output 同时使用。".to_string()));
+            }
+        }
+        
+        // 验证整体风险评分权重
+        for weight in [self.risk_weight_max, self.risk_weight_secret, self.risk_weight_5xx, self.risk_weight_403] {
+            if !weight.is_finite() || weight < 0.0 {
+                return Err(ScanError::InvalidConfig("风险评分权重必须是非负数。".to_string()));
             }
         }
         
-        // 验证UA文件
-        if !self.user_agent_file.exists() {
-            return Err(ScanError::InvalidConfig("UA文件不存在。".to_string()));
+        // 验证TLS版本范围
+        if let (Some(min), Some(max)) = (&self.min_tls, &self.max_tls)
+            && min > max
+        {
+            return Err(ScanError::InvalidConfig("--min-tls 不能高于 --max-tls。".to_string()));
         }
-        if std::fs::metadata(&self.user_agent_file)?.len() == 0 {
-            return Err(ScanError::InvalidConfig("UA文件不能为空.".to_string()));
+        
+        // 验证DNS固定解析
+        for entry in &self.resolve {
+            parse_resolve(entry)?;
+        }
+        
+        // 验证代理认证
+        if let Some(proxy_auth) = &self.proxy_auth {
+            if self.proxy.is_none() {
+                return Err(ScanError::InvalidConfig("--proxy-auth 需要同时指定 --proxy。".to_string()));
+            }
+            match proxy_auth.split_once(':') {
+                Some((user, _)) if !user.is_empty() => {}
+                _ => return Err(ScanError::InvalidConfig("代理认证格式应为 用户名:密码。".to_string())),
+            }
+        }
+        
+        // 跳过证书验证时给出醒目警告
+        if self.insecure {
+            warn!("==================================================");
+            warn!("  已启用 --insecure: TLS证书验证已关闭!");
+            warn!("  连接可能遭受中间人攻击, 仅应用于可信的内部主机");
+            warn!("==================================================");
+        }
+        
+        // 规则与Content-Type的对应关系，传空字符串时所有规则适用于所有响应
+        for entry in self.rule_content_types.iter().filter(|entry| !entry.trim().is_empty()) {
+            match entry.split_once('=') {
+                Some((rule, types)) if !rule.trim().is_empty() && types.split('|').any(|t| !t.trim().is_empty()) => {}
+                _ => return Err(ScanError::InvalidConfig(format!(
+                    "--rule-content-types 格式应为 规则名=类型1|类型2: {}", entry
+                ))),
+            }
+        }
+        
+        // 验证UA，指定了 --user-agent 时不需要UA文件
+        if let Some(user_agent) = &self.user_agent {
+            if user_agent.trim().is_empty() || reqwest::header::HeaderValue::from_str(user_agent).is_err() {
+                return Err(ScanError::InvalidConfig("--user-agent 不能为空，且不能包含换行等控制字符。".to_string()));
+            }
+        } else if self.replay.is_none() {
+            if !self.user_agent_file.exists() {
+                return Err(ScanError::InvalidConfig("UA文件不存在。".to_string()));
+            }
+            if std::fs::metadata(&self.user_agent_file)?.len() == 0 {
+                return Err(ScanError::InvalidConfig("UA文件不能为空.".to_string()));
+            }
         }
         
         Ok(())
     }
-}
\ No newline at end of file
+
+    // 构建认证请求头的值
+    pub fn authorization_header(&self) -> Option<String> {
+        if let Some(token) = &self.auth_token {
+            return Some(self.token_header_value(token));
+        }
+        self.basic_auth.as_ref()
+            .map(|credentials| format!("Basic {}", STANDARD.encode(credentials)))
+    }
+
+    // 请求该URL时使用的认证头的值：--host-tokens 中有该主机的令牌时优先使用
+    pub fn authorization_header_for(&self, url: &str) -> Option<String> {
+        self.host_authorization(url).or_else(|| self.authorization_header())
+    }
+
+    // --host-tokens 中该URL所属主机的认证头的值，没有时返回None
+    pub fn host_authorization(&self, url: &str) -> Option<String> {
+        if self.host_auth_tokens.is_empty() {
+            return None;
+        }
+        let origin = Url::parse(url).ok()?.origin().ascii_serialization();
+        self.host_auth_tokens.iter()
+            .find(|(host, _)| *host == origin)
+            .map(|(_, token)| self.token_header_value(token))
+    }
+
+    // 按 --auth-scheme 组装令牌请求头的值，方案为空时直接使用令牌
+    pub fn token_header_value(&self, token: &str) -> String {
+        let scheme = self.auth_scheme.trim();
+        if scheme.is_empty() {
+            token.to_string()
+        } else {
+            format!("{} {}", scheme, token)
+        }
+    }
+
+    // 认证请求头名称，Basic认证总是使用Authorization
+    pub fn auth_header_name(&self) -> &str {
+        if self.basic_auth.is_some() {
+            "Authorization"
+        } else {
+            &self.auth_header_name
+        }
+    }
+
+    // 需要在日志和报告中脱敏的凭据
+    pub fn credential_secrets(&self) -> Vec<String> {
+        let mut secrets = Vec::new();
+        // 包括由 --auth-token-env/--auth-token-file 读取的令牌
+        if let Some(token) = &self.auth_token {
+            secrets.push(token.clone());
+        }
+        secrets.extend(self.host_auth_tokens.iter().map(|(_, token)| token.clone()));
+        if let Some(credentials) = &self.basic_auth {
+            secrets.push(STANDARD.encode(credentials));
+            if let Some((_, password)) = credentials.split_once(':')
+                && !password.is_empty()
+            {
+                secrets.push(password.to_string());
+            }
+        }
+        secrets
+    }
+
+    // --methods 解析为大写的HTTP方法，去除重复项并保持顺序；无效的方法已在 validate 中报错
+    pub fn http_methods(&self) -> Vec<reqwest::Method> {
+        let mut methods: Vec<reqwest::Method> = Vec::new();
+        for method in &self.methods {
+            if let Ok(method) = reqwest::Method::from_bytes(method.trim().to_ascii_uppercase().as_bytes())
+                && !methods.contains(&method)
+            {
+                methods.push(method);
+            }
+        }
+        methods
+    }
+
+    // 规范化的目标URL（含 --base-path），不带末尾斜杠，便于与路径拼接
+    // --absolute-urls 时路径本身就是完整URL，返回空字符串
+    pub fn base_url(&self) -> String {
+        let Some(target) = self.target.as_deref().filter(|_| !self.absolute_urls) else {
+            return String::new();
+        };
+        let target = match parse_target(target) {
+            Ok(url) => url.as_str().trim_end_matches('/').to_string(),
+            Err(_) => target.trim_end_matches('/').to_string(),
+        };
+        match &self.base_path {
+            Some(base_path) => join_base_path(&target, base_path),
+            None => target,
+        }
+    }
+
+    // 检查JWT内容，避免使用不安全或已失效的令牌浪费整个扫描
+    fn inspect_jwt(&self, token: &str) -> Result<(), ScanError> {
+        let (header, claims) = match decode_jwt(token) {
+            Ok(decoded) => decoded,
+            Err(e) if self.strict_token => return Err(ScanError::InvalidConfig(e.to_string())),
+            Err(e) => {
+                warn!("无法解析JWT令牌: {}", e);
+                return Ok(());
+            }
+        };
+
+        info!("JWT头部: {}", header);
+        info!("JWT声明: {}", claims);
+
+        let mut problems = Vec::new();
+
+        // 检查签名算法
+        if let Some(alg) = header.get("alg").and_then(Value::as_str)
+            && alg.eq_ignore_ascii_case("none")
+        {
+            problems.push("JWT令牌使用了不安全的签名算法 alg=none".to_string());
+        }
+
+        // 检查过期时间
+        if let Some(exp) = claims.get("exp").and_then(Value::as_i64) {
+            let now = chrono::Utc::now().timestamp();
+            let expires_at = chrono::DateTime::from_timestamp(exp, 0)
+                .map(|t| t.with_timezone(&chrono::Local).to_string())
+                .unwrap_or_else(|| exp.to_string());
+            if exp <= now {
+                problems.push(format!("JWT令牌已过期 (过期时间: {})", expires_at));
+            } else if exp - now <= self.token_expiry_warn {
+                problems.push(format!("JWT令牌将在 {} 秒后过期 (过期时间: {})", exp - now, expires_at));
+            }
+        }
+
+        for problem in problems {
+            if self.strict_token {
+                return Err(ScanError::InvalidConfig(problem));
+            }
+            warn!("{}", problem);
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // 从标准输入读取字典并固定UA，避免 validate 依赖 ./config 下的文件
+    fn test_config(args: &[&str]) -> Config {
+        Config::from_iter_safe([&["api_scan", "-d", "-", "--user-agent", "test"], args].concat()).unwrap()
+    }
+
+    #[test]
+    fn malformed_targets_are_rejected() {
+        for target in ["ftp://x", "http://", "not a url", "", "127.0.0.1:8080", "http://x/?q=1", "http://x/#top"] {
+            assert!(matches!(parse_target(target), Err(ScanError::InvalidConfig(_))), "{:?}", target);
+            let config = test_config(&["-t", target]);
+            assert!(matches!(config.validate(), Err(ScanError::InvalidConfig(_))), "{:?}", target);
+        }
+        assert!(test_config(&["-t", "https://api.example.com/v1"]).validate().is_ok());
+    }
+
+    #[test]
+    fn basic_auth_header_is_base64_encoded() {
+        let config = test_config(&["-t", "http://127.0.0.1:8080", "--basic-auth", "user:pass"]);
+        assert!(config.validate().is_ok());
+        assert_eq!(config.authorization_header().as_deref(), Some("Basic dXNlcjpwYXNz"));
+        assert_eq!(config.auth_header_name(), "Authorization");
+
+        let config = test_config(&["-t", "http://127.0.0.1:8080", "--basic-auth", "user:pass", "--auth-token", "abc"]);
+        assert!(matches!(config.validate(), Err(ScanError::InvalidConfig(_))));
+    }
+
+    #[test]
+    fn ipv6_and_raw_ip_targets() {
+        let url = parse_target("http://[::1]:8080").unwrap();
+        assert_eq!(url.host_str(), Some("[::1]"));
+        assert_eq!(url.port(), Some(8080));
+        for target in ["http://[::1]:8080", "https://[2001:db8::1]/api", "http://10.0.0.5", "http://10.0.0.5:8080/"] {
+            assert!(test_config(&["-t", target]).validate().is_ok(), "{}", target);
+        }
+        assert_eq!(test_config(&["-t", "http://[::1]:8080/", "--base-path", "api"]).base_url(), "http://[::1]:8080/api");
+        // 未加方括号的IPv6地址无法与端口区分
+        assert!(matches!(parse_target("http://::1:8080"), Err(ScanError::InvalidConfig(_))));
+    }
+
+    #[test]
+    fn host_header_with_raw_ip_target() {
+        for host in ["api.internal", "api.internal:8443", "[::1]:8443", "10.0.0.5"] {
+            let config = test_config(&["-t", "http://10.0.0.5:8080", "--host-header", host]);
+            assert!(config.validate().is_ok(), "{}", host);
+        }
+        for host in ["api.internal/admin", "user@api.internal", "::1", ""] {
+            let config = test_config(&["-t", "http://[::1]:8080", "--host-header", host]);
+            assert!(matches!(config.validate(), Err(ScanError::InvalidConfig(_))), "{:?}", host);
+        }
+    }
+
+    #[test]
+    fn resolved_auth_token_is_redacted() {
+        // SAFETY: 测试中只有这里使用该环境变量
+        unsafe { std::env::set_var("API_SCAN_TEST_TOKEN", " env-token-123 \n") };
+        let mut config = test_config(&["-t", "http://127.0.0.1:8080", "--auth-token-env", "API_SCAN_TEST_TOKEN"]);
+        config.resolve_auth_token().unwrap();
+        assert!(config.validate().is_ok());
+        assert_eq!(config.credential_secrets(), ["env-token-123"]);
+    }
+}
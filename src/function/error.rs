@@ -27,6 +27,12 @@ pub enum ScanError {
     
     #[error("序列化错误: {0}")]
     SerializationError(String),
+    
+    #[error("TLS错误: {0}")]
+    TlsError(String),
+    
+    #[error("认证错误: {0}")]
+    AuthError(String),
 }
  
 
@@ -36,9 +42,36 @@ impl From<io::Error> for ScanError {
     }
 }
 
+// 沿错误链查找TLS握手/证书相关的错误
+fn tls_error_detail(err: &reqwest::Error) -> Option<String> {
+    let mut source = std::error::Error::source(err);
+    while let Some(inner) = source {
+        let message = inner.to_string();
+        let lower = message.to_ascii_lowercase();
+        if lower.contains("certificate") || lower.contains("tls") || lower.contains("ssl") || lower.contains("handshake") {
+            return Some(message);
+        }
+        source = inner.source();
+    }
+    None
+}
+
+// 握手因双方没有共同支持的TLS版本而失败
+fn is_tls_version_error(detail: &str) -> bool {
+    let lower = detail.to_ascii_lowercase();
+    lower.contains("protocol version") || lower.contains("unsupported protocol") || lower.contains("no protocols available")
+}
+
 impl From<reqwest::Error> for ScanError {
     fn from(err: reqwest::Error) -> Self {
-        if err.is_timeout() {
+        if let Some(detail) = tls_error_detail(&err) {
+            let hint = if is_tls_version_error(&detail) {
+                "没有双方都支持的TLS版本，请检查 --min-tls/--max-tls"
+            } else {
+                "自签名证书可使用 --insecure 跳过验证"
+            };
+            ScanError::TlsError(format!("TLS握手失败: {} ({})", detail, hint))
+        } else if err.is_timeout() {
             ScanError::NetworkError(format!("请求超时: {}", err))
         } else if err.is_connect() {
             ScanError::NetworkError(format!("连接错误: {}", err))
@@ -48,6 +81,18 @@ impl From<reqwest::Error> for ScanError {
     }
 }
 
+impl ScanError {
+    // 请求失败的分类，沿用 From<reqwest::Error> 中的区分，用于报告中按类别统计
+    pub fn failure_category(&self) -> &'static str {
+        match self {
+            ScanError::TlsError(_) => "TLS错误",
+            ScanError::NetworkError(message) if message.starts_with("请求超时") => "超时",
+            ScanError::NetworkError(_) => "连接错误",
+            _ => "其他",
+        }
+    }
+}
+
 impl From<serde_json::Error> for ScanError {
     fn from(err: serde_json::Error) -> Self {
         ScanError::SerializationError(err.to_string())
@@ -0,0 +1,309 @@
+// har.rs
+// 将扫描中的请求和响应记录为HAR 1.2格式，可导入浏览器开发者工具或Burp
+use super::ScanError;
+use super::vulnerability::REDACTED;
+use chrono::{DateTime, Local};
+use log::info;
+use base64::Engine;
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::Path;
+use std::sync::Mutex;
+
+#[derive(Debug, Serialize)]
+struct HarFile<'a> {
+    log: HarLog<'a>,
+}
+
+#[derive(Debug, Serialize)]
+struct HarLog<'a> {
+    version: &'static str,
+    creator: HarCreator,
+    entries: &'a [HarEntry],
+}
+
+#[derive(Debug, Serialize)]
+struct HarCreator {
+    name: &'static str,
+    version: &'static str,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct HarEntry {
+    started_date_time: String,
+    time: u64,
+    request: HarRequest,
+    response: HarResponse,
+    cache: HarCache,
+    timings: HarTimings,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct HarRequest {
+    method: String,
+    url: String,
+    http_version: String,
+    headers: Vec<HarPair>,
+    query_string: Vec<HarPair>,
+    cookies: Vec<HarPair>,
+    headers_size: i64,
+    body_size: i64,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct HarResponse {
+    status: u16,
+    status_text: String,
+    http_version: String,
+    headers: Vec<HarPair>,
+    cookies: Vec<HarPair>,
+    content: HarContent,
+    #[serde(rename = "redirectURL")]
+    redirect_url: String,
+    headers_size: i64,
+    body_size: i64,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct HarContent {
+    size: i64,
+    mime_type: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    text: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    comment: Option<String>,
+}
+
+#[derive(Debug, Serialize)]
+struct HarPair {
+    name: String,
+    value: String,
+}
+
+#[derive(Debug, Serialize)]
+struct HarCache {}
+
+// 只记录从发出请求到收到响应头的总时间，其余阶段HAR中以-1表示不可用
+#[derive(Debug, Serialize)]
+struct HarTimings {
+    blocked: i64,
+    dns: i64,
+    connect: i64,
+    send: u64,
+    wait: u64,
+    receive: u64,
+}
+
+impl HarEntry {
+    // 在读取响应体之前记录请求和响应元数据，认证头的值以[REDACTED]代替
+    pub fn new(
+        method: &reqwest::Method,
+        url: &str,
+        request_headers: &[(&str, String)],
+        auth_header: Option<&str>,
+        response: &reqwest::Response,
+        started: DateTime<Local>,
+        time: u64,
+    ) -> Self {
+        let http_version = format!("{:?}", response.version());
+        let mut headers: Vec<HarPair> = request_headers.iter()
+            .map(|(name, value)| HarPair { name: name.to_string(), value: value.clone() })
+            .collect();
+        if let Some(name) = auth_header {
+            headers.push(HarPair { name: name.to_string(), value: REDACTED.to_string() });
+        }
+        let query_string = url::Url::parse(url)
+            .map(|parsed| parsed.query_pairs()
+                .map(|(name, value)| HarPair { name: name.into_owned(), value: value.into_owned() })
+                .collect())
+            .unwrap_or_default();
+        let response_headers: Vec<HarPair> = response.headers().iter()
+            .map(|(name, value)| HarPair {
+                name: name.as_str().to_string(),
+                value: String::from_utf8_lossy(value.as_bytes()).into_owned(),
+            })
+            .collect();
+        let mime_type = response.headers()
+            .get(reqwest::header::CONTENT_TYPE)
+            .and_then(|v| v.to_str().ok())
+            .unwrap_or("")
+            .to_string();
+        let body_size = response.content_length().map(|len| len as i64).unwrap_or(-1);
+
+        HarEntry {
+            started_date_time: started.to_rfc3339(),
+            time,
+            request: HarRequest {
+                method: method.to_string(),
+                url: url.to_string(),
+                http_version: http_version.clone(),
+                headers,
+                query_string,
+                cookies: Vec::new(),
+                headers_size: -1,
+                body_size: 0,
+            },
+            response: HarResponse {
+                status: response.status().as_u16(),
+                status_text: response.status().canonical_reason().unwrap_or("").to_string(),
+                http_version,
+                headers: response_headers,
+                cookies: Vec::new(),
+                content: HarContent {
+                    size: body_size,
+                    mime_type,
+                    text: None,
+                    comment: None,
+                },
+                redirect_url: String::new(),
+                headers_size: -1,
+                body_size,
+            },
+            cache: HarCache {},
+            timings: HarTimings {
+                blocked: -1,
+                dns: -1,
+                connect: -1,
+                send: 0,
+                wait: time,
+                receive: 0,
+            },
+        }
+    }
+
+    // 记录解码后的响应体文本（已受 --max-body-bytes 限制）
+    pub fn set_body(&mut self, text: &str, truncated: bool) {
+        self.response.content.size = text.len() as i64;
+        self.response.content.text = Some(text.to_string());
+        if truncated {
+            self.response.content.comment = Some("响应体超过 --max-body-bytes，已截断".to_string());
+        }
+    }
+}
+
+// 并发任务共享的HAR记录器，扫描结束后一次性写入文件
+#[derive(Default)]
+pub struct HarRecorder {
+    entries: Mutex<Vec<HarEntry>>,
+}
+
+impl HarRecorder {
+    pub fn record(&self, entry: HarEntry) {
+        self.entries.lock().unwrap().push(entry);
+    }
+
+    pub fn save(&self, path: &Path) -> Result<(), ScanError> {
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)
+                .map_err(|e| ScanError::IOError(format!("无法创建输出目录: {}", e)))?;
+        }
+        let mut entries = self.entries.lock().unwrap();
+        entries.sort_by(|a, b| a.started_date_time.cmp(&b.started_date_time));
+        let har = HarFile {
+            log: HarLog {
+                version: "1.2",
+                creator: HarCreator {
+                    name: env!("CARGO_PKG_NAME"),
+                    version: env!("CARGO_PKG_VERSION"),
+                },
+                entries: &entries,
+            },
+        };
+        fs::write(path, serde_json::to_string_pretty(&har)?)
+            .map_err(|e| ScanError::IOError(format!("写入HAR文件失败: {}", e)))?;
+        info!("HAR已保存至: {:?} ({} 个请求)", path, entries.len());
+        Ok(())
+    }
+}
+
+// --replay 读取的HAR条目，只保留重新检测需要的字段；也可读取浏览器导出的HAR
+#[derive(Debug)]
+pub struct ReplayEntry {
+    pub method: String,
+    pub url: String,
+    pub status: u16,
+    pub headers: Vec<(String, String)>,
+    pub mime_type: String,
+    pub text: Option<String>,
+    pub truncated: bool,
+    pub time: u64,
+}
+
+#[derive(Deserialize)]
+struct ReplayFile {
+    log: ReplayLog,
+}
+
+#[derive(Deserialize)]
+struct ReplayLog {
+    entries: Vec<ReplayHarEntry>,
+}
+
+#[derive(Deserialize)]
+struct ReplayHarEntry {
+    #[serde(default)]
+    time: f64,
+    request: ReplayRequest,
+    response: ReplayResponse,
+}
+
+#[derive(Deserialize)]
+struct ReplayRequest {
+    method: String,
+    url: String,
+}
+
+#[derive(Deserialize)]
+struct ReplayResponse {
+    status: u16,
+    #[serde(default)]
+    headers: Vec<ReplayPair>,
+    content: ReplayContent,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct ReplayContent {
+    #[serde(default)]
+    mime_type: String,
+    text: Option<String>,
+    encoding: Option<String>,
+    comment: Option<String>,
+}
+
+#[derive(Deserialize)]
+struct ReplayPair {
+    name: String,
+    value: String,
+}
+
+// 读取HAR文件中的请求和响应，base64编码的响应体解码后按UTF-8 (有损) 处理
+pub fn load_har(path: &Path) -> Result<Vec<ReplayEntry>, ScanError> {
+    let content = fs::read_to_string(path)
+        .map_err(|e| ScanError::IOError(format!("无法读取HAR文件: {}", e)))?;
+    let har: ReplayFile = serde_json::from_str(&content)
+        .map_err(|e| ScanError::SerializationError(format!("HAR文件格式错误: {}", e)))?;
+    Ok(har.log.entries.into_iter().map(|entry| {
+        let content = entry.response.content;
+        let text = match (content.text, content.encoding.as_deref()) {
+            (Some(text), Some("base64")) => base64::engine::general_purpose::STANDARD.decode(text.trim())
+                .map(|bytes| String::from_utf8_lossy(&bytes).into_owned())
+                .ok(),
+            (text, _) => text,
+        };
+        ReplayEntry {
+            method: entry.request.method,
+            url: entry.request.url,
+            status: entry.response.status,
+            headers: entry.response.headers.into_iter().map(|pair| (pair.name, pair.value)).collect(),
+            mime_type: content.mime_type,
+            text,
+            truncated: content.comment.is_some(),
+            time: entry.time.max(0.0) as u64,
+        }
+    }).collect())
+}
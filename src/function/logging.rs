@@ -0,0 +1,55 @@
+// 日志板块
+use indicatif::MultiProgress;
+use log::{LevelFilter, Log, Metadata, Record};
+use std::sync::OnceLock;
+
+static PROGRESS: OnceLock<MultiProgress> = OnceLock::new();
+
+// 全局进度条容器，日志输出时会暂停进度条绘制，避免输出错乱
+pub fn multi_progress() -> &'static MultiProgress {
+    PROGRESS.get_or_init(MultiProgress::new)
+}
+
+struct ProgressAwareLogger {
+    inner: env_logger::Logger,
+}
+
+impl Log for ProgressAwareLogger {
+    fn enabled(&self, metadata: &Metadata) -> bool {
+        self.inner.enabled(metadata)
+    }
+
+    fn log(&self, record: &Record) {
+        if self.inner.matches(record) {
+            multi_progress().suspend(|| self.inner.log(record));
+        }
+    }
+
+    fn flush(&self) {
+        self.inner.flush();
+    }
+}
+
+// 根据 -v 次数设置日志级别:
+// 默认只输出警告和错误, -v 输出每个路径的结果, -vv 输出完整的请求/响应元数据, --quiet 只输出错误
+pub fn init_logger(verbosity: u8, quiet: bool) {
+    let level = match verbosity {
+        _ if quiet => LevelFilter::Error,
+        0 => LevelFilter::Warn,
+        1 => LevelFilter::Info,
+        2 => LevelFilter::Debug,
+        _ => LevelFilter::Trace,
+    };
+
+    // RUST_LOG 环境变量可以进一步覆盖
+    let inner = env_logger::Builder::new()
+        .filter_level(level)
+        .format_timestamp(None)
+        .parse_default_env()
+        .build();
+    let max_level = inner.filter();
+
+    if log::set_boxed_logger(Box::new(ProgressAwareLogger { inner })).is_ok() {
+        log::set_max_level(max_level);
+    }
+}
@@ -3,9 +3,19 @@ pub mod scanner;
 pub mod vulnerability;
 pub mod report;
 pub mod error;
+pub mod logging;
+pub mod openapi;
+pub mod baseline;
+pub mod decode;
+pub mod har;
+pub mod sitemap;
+pub mod webhook;
+pub mod timing;
+pub mod waf;
+pub mod tls;
 
 pub use self::config::Config;
-pub use self::scanner::run_scan;
+pub use self::scanner::{run_scan, scan, replay, scan_with_progress, scan_with_client_builder, save_report, print_summary, print_findings_table, ComprehensiveScanReport, ScanEvent};
 pub use self::report::ScanResult;
 pub use self::error::ScanError;
 
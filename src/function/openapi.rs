@@ -0,0 +1,120 @@
+// openapi.rs
+// 从 Swagger 2.0 / OpenAPI 3.x 的JSON规范中提取API路径
+use super::ScanError;
+use serde_json::Value;
+use std::fs;
+use std::path::Path;
+
+const HTTP_METHODS: [&str; 8] = ["get", "put", "post", "delete", "options", "head", "patch", "trace"];
+
+#[derive(Debug, Clone)]
+pub struct OpenApiEndpoint {
+    pub path: String,
+    pub methods: Vec<String>,
+}
+
+pub fn load_openapi_endpoints(file: &Path, placeholder: &str) -> Result<Vec<OpenApiEndpoint>, ScanError> {
+    let content = fs::read_to_string(file)
+        .map_err(|e| ScanError::IOError(format!("无法读取OpenAPI文件: {}", e)))?;
+    let spec: Value = serde_json::from_str(&content)
+        .map_err(|e| ScanError::ParseError(format!("OpenAPI文件不是有效的JSON: {}", e)))?;
+    parse_openapi_endpoints(&spec, placeholder)
+}
+
+pub fn parse_openapi_endpoints(spec: &Value, placeholder: &str) -> Result<Vec<OpenApiEndpoint>, ScanError> {
+    let paths = spec.get("paths")
+        .and_then(Value::as_object)
+        .ok_or_else(|| ScanError::ParseError("OpenAPI文件缺少paths对象".to_string()))?;
+
+    let base_path = base_path(spec);
+    let mut endpoints = Vec::new();
+
+    for (template, item) in paths {
+        let Some(item) = item.as_object() else { continue };
+
+        // 路径级别的参数对所有方法生效
+        let mut parameters: Vec<&Value> = item.get("parameters")
+            .and_then(Value::as_array)
+            .map(|params| params.iter().collect())
+            .unwrap_or_default();
+
+        let mut methods = Vec::new();
+        for method in HTTP_METHODS {
+            if let Some(operation) = item.get(method) {
+                methods.push(method.to_ascii_uppercase());
+                if let Some(params) = operation.get("parameters").and_then(Value::as_array) {
+                    parameters.extend(params.iter());
+                }
+            }
+        }
+
+        let path = expand_template(template, &parameters, placeholder);
+        endpoints.push(OpenApiEndpoint {
+            path: format!("{}{}", base_path, path),
+            methods,
+        });
+    }
+
+    Ok(endpoints)
+}
+
+// Swagger 2.0 使用 basePath，OpenAPI 3.x 使用 servers[0].url
+fn base_path(spec: &Value) -> String {
+    let raw = spec.get("basePath")
+        .and_then(Value::as_str)
+        .or_else(|| {
+            spec.get("servers")
+                .and_then(Value::as_array)
+                .and_then(|servers| servers.first())
+                .and_then(|server| server.get("url"))
+                .and_then(Value::as_str)
+        })
+        .unwrap_or("");
+
+    // 绝对URL只取路径部分
+    let path = match raw.find("://") {
+        Some(idx) => raw[idx + 3..].find('/').map(|p| &raw[idx + 3 + p..]).unwrap_or(""),
+        None => raw,
+    };
+    path.trim_end_matches('/').to_string()
+}
+
+// 将 /users/{id} 中的参数替换为规范中的示例值，没有示例时使用占位值
+fn expand_template(template: &str, parameters: &[&Value], placeholder: &str) -> String {
+    let mut result = String::with_capacity(template.len());
+    let mut rest = template;
+
+    while let Some(start) = rest.find('{') {
+        let Some(end) = rest[start..].find('}') else { break };
+        let name = &rest[start + 1..start + end];
+        result.push_str(&rest[..start]);
+        result.push_str(&sample_value(name, parameters).unwrap_or_else(|| placeholder.to_string()));
+        rest = &rest[start + end + 1..];
+    }
+    result.push_str(rest);
+    result
+}
+
+fn sample_value(name: &str, parameters: &[&Value]) -> Option<String> {
+    let param = parameters.iter().find(|p| {
+        p.get("name").and_then(Value::as_str) == Some(name)
+            && p.get("in").and_then(Value::as_str) == Some("path")
+    })?;
+
+    let schema = param.get("schema");
+    [
+        param.get("example"),
+        param.get("x-example"),
+        schema.and_then(|s| s.get("example")),
+        param.get("default"),
+        schema.and_then(|s| s.get("default")),
+    ]
+    .into_iter()
+    .flatten()
+    .find_map(|value| match value {
+        Value::String(s) => Some(s.clone()),
+        Value::Number(n) => Some(n.to_string()),
+        Value::Bool(b) => Some(b.to_string()),
+        _ => None,
+    })
+}
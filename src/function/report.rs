@@ -1,14 +1,394 @@
-// use super::ScanError;
+use super::ScanError;
+use super::scanner::{failure_tally, findings_by_type, findings_count, ComprehensiveScanReport, OverallRisk};
+use super::vulnerability::SensitiveInfoFinding;
 use serde::{Serialize, Deserialize};
-// use std::fs;
-// use std::path::Path;
+use std::collections::{BTreeMap, HashMap};
+use std::fs;
+use std::fs::File;
+use std::io::{BufWriter, Write};
+use std::path::Path;
+use std::sync::Mutex;
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ScanResult {
     pub path: String,
     pub url: String,
+    #[serde(default = "default_method")]
+    pub method: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub allow: Option<String>,      // 响应的Allow头，OPTIONS请求可借此列出端点支持的方法
     pub status_code: u16,
     pub content_length: usize,
     pub response_time: u64,
     pub found: bool,
-}
\ No newline at end of file
+    #[serde(default)]
+    pub content_encoding: Option<String>,
+    #[serde(default)]
+    pub truncated: bool,
+    #[serde(default)]
+    pub charset: Option<String>,
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    pub headers: HashMap<String, String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub payload: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub body_hash: Option<String>,  // 解码后响应体的SHA-256
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub download_time: Option<u64>, // --detailed-timing 时读取响应体的耗时 (毫秒)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub content_type: Option<String>, // 响应的MIME类型，不含charset等参数
+}
+
+// GET请求以URL作为用例名，其他方法加上方法前缀，如 "OPTIONS https://example.com/api"
+fn testcase_name(result: &ScanResult) -> String {
+    if result.method == "GET" {
+        result.url.clone()
+    } else {
+        format!("{} {}", result.method, result.url)
+    }
+}
+
+// 旧报告中的结果都是GET请求
+fn default_method() -> String {
+    "GET".to_string()
+}
+
+// 流式输出模式下，报告中只保留计数
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct StreamedCounts {
+    pub output: String,
+    pub results: usize,
+    pub successes: usize,
+    pub findings: usize,
+    pub findings_by_type: HashMap<String, usize>,
+}
+
+// JSON Lines 写入器：每条结果或发现一行，产生后立即追加写入
+pub struct JsonLinesWriter {
+    writer: Mutex<BufWriter<File>>,
+}
+
+impl JsonLinesWriter {
+    pub fn create(path: &Path) -> Result<Self, ScanError> {
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)
+                .map_err(|e| ScanError::IOError(format!("无法创建输出目录: {}", e)))?;
+        }
+        let file = File::create(path)
+            .map_err(|e| ScanError::IOError(format!("无法创建流式输出文件: {}", e)))?;
+        Ok(JsonLinesWriter {
+            writer: Mutex::new(BufWriter::new(file)),
+        })
+    }
+
+    // 写入一行 {"type": kind, "data": item}
+    pub fn write_line<T: Serialize>(&self, kind: &str, item: &T) -> Result<(), ScanError> {
+        let line = serde_json::to_string(&serde_json::json!({ "type": kind, "data": item }))?;
+        let mut writer = self.writer.lock().unwrap();
+        writeln!(writer, "{}", line)?;
+        Ok(())
+    }
+
+    pub fn flush(&self) -> Result<(), ScanError> {
+        self.writer.lock().unwrap().flush()?;
+        Ok(())
+    }
+}
+
+// 转义Markdown表格单元格中的特殊字符，避免表格错位
+fn escape_md_cell(value: &str) -> String {
+    value
+        .replace('\\', "\\\\")
+        .replace('|', "\\|")
+        .replace('\r', "")
+        .replace('\n', "<br>")
+}
+
+// 生成Markdown格式的报告内容
+pub fn render_markdown_report(report: &ComprehensiveScanReport) -> String {
+    let mut md = String::new();
+
+    // 摘要头部
+    md.push_str("# API扫描报告\n\n");
+    md.push_str(&format!("- **扫描目标**: {}\n", report.scan_config.target));
+    md.push_str(&format!("- **扫描路径数**: {}\n", report.scan_config.paths_scanned));
+    if let Some(sample) = &report.scan_config.sample {
+        md.push_str(&format!("- **抽样扫描**: 从 {} 个路径中随机抽取 {} 个 (种子 {})\n", sample.total_paths, sample.sampled_paths, sample.seed));
+    }
+    md.push_str(&format!("- **扫描时间**: {} 秒\n", report.scan_duration));
+    md.push_str(&format!("- **扫描时间戳**: {}\n", report.scan_timestamp));
+    if report.time_budget_exceeded {
+        md.push_str(&format!("- **注意**: 已达到时间预算 (--max-duration)，{} 个请求未扫描，本报告只包含部分结果\n", report.unscanned));
+    } else if report.max_requests_reached {
+        md.push_str(&format!("- **注意**: 已达到请求数上限 (--max-requests)，{} 个请求未扫描，本报告只包含部分结果\n", report.unscanned));
+    } else if report.partial {
+        md.push_str(&format!("- **注意**: 扫描被中断，{} 个请求未扫描，本报告只包含部分结果\n", report.unscanned));
+    }
+    md.push_str(&format!("- **整体风险**: {} 分 (等级 {})\n", report.overall_risk.score, report.overall_risk.grade));
+    if let Some(preflight) = &report.preflight {
+        match preflight.status {
+            Some(status) => md.push_str(&format!("- **预检**: 状态码 {}，耗时 {:.1} ms\n", status, preflight.latency_ms)),
+            None => md.push_str(&format!("- **预检**: 请求失败 ({})\n", preflight.error.as_deref().unwrap_or("-"))),
+        }
+    }
+    if let Some(waf) = &report.waf_check {
+        md.push_str(&format!("- **WAF检测**: {}\n", waf.description()));
+    }
+    for cert in &report.tls_certificates {
+        match &cert.error {
+            Some(error) => md.push_str(&format!("- **TLS证书** {}: 读取失败 ({})\n", cert.host, error)),
+            None => md.push_str(&format!(
+                "- **TLS证书** {}: {}，颁发者 {}，{} 到期 (剩余 {} 天){}\n",
+                cert.host,
+                cert.subject.as_deref().unwrap_or("-"),
+                cert.issuer.as_deref().unwrap_or("-"),
+                cert.not_after.as_deref().unwrap_or("-"),
+                cert.days_remaining.unwrap_or(0),
+                if cert.self_signed { "，自签名" } else { "" },
+            )),
+        }
+    }
+    md.push_str(&format!("- **5xx错误**: {}\n", report.error_count));
+    if report.rate_limited > 0 {
+        md.push_str(&format!("- **429限流**: {}\n", report.rate_limited));
+    }
+    if report.circuit_breaker_trips > 0 {
+        md.push_str(&format!("- **熔断**: 错误率达到阈值，触发 {} 次\n", report.circuit_breaker_trips));
+    }
+    if report.concurrency_curve.len() > 1 {
+        let curve = report.concurrency_curve.iter()
+            .map(|point| point.concurrency.to_string())
+            .collect::<Vec<_>>()
+            .join(" → ");
+        md.push_str(&format!("- **自动并发**: {}\n", curve));
+    }
+    if !report.failed_requests.is_empty() {
+        md.push_str(&format!("- **失败请求**: {} ({})\n", report.failed_requests.len(), failure_tally(&report.failed_requests)));
+    }
+    md.push_str(&format!("- **403禁止访问**: {}\n\n", report.forbidden_urls.len()));
+
+    // 敏感信息按类型分组
+    md.push_str("## 敏感信息发现\n\n");
+    if report.sensitive_findings.is_empty() {
+        md.push_str("未发现敏感信息\n\n");
+    } else {
+        let mut groups: HashMap<&str, Vec<&SensitiveInfoFinding>> = HashMap::new();
+        for finding in &report.sensitive_findings {
+            groups.entry(finding.info_type.as_str()).or_default().push(finding);
+        }
+
+        // 按组内最高风险评分降序排列，评分相同时按类型名排序保证输出稳定
+        let mut groups: Vec<_> = groups.into_iter().collect();
+        groups.sort_by(|a, b| {
+            let a_score = a.1.iter().map(|f| f.risk_score).max().unwrap_or(0);
+            let b_score = b.1.iter().map(|f| f.risk_score).max().unwrap_or(0);
+            b_score.cmp(&a_score).then_with(|| a.0.cmp(b.0))
+        });
+
+        for (info_type, mut findings) in groups {
+            findings.sort_by_key(|f| std::cmp::Reverse(f.risk_score));
+            md.push_str(&format!("### {} ({}项)\n\n", escape_md_cell(info_type), findings.len()));
+            md.push_str("| 风险评分 | URL | 匹配内容 |\n");
+            md.push_str("|---|---|---|\n");
+            for finding in findings {
+                md.push_str(&format!(
+                    "| {} | {} | `{}` |\n",
+                    finding.risk_score,
+                    escape_md_cell(&finding.url),
+                    escape_md_cell(&finding.matched_content).replace('`', "'"),
+                ));
+            }
+            md.push('\n');
+        }
+    }
+
+    // 去重后的敏感值
+    if !report.unique_secrets.is_empty() {
+        md.push_str("## 泄露的敏感值\n\n");
+        md.push_str("| 风险评分 | 类型 | 值 | URL数 |\n");
+        md.push_str("|---|---|---|---|\n");
+        for secret in &report.unique_secrets {
+            md.push_str(&format!(
+                "| {} | {} | `{}` | {} |\n",
+                secret.risk_score,
+                escape_md_cell(&secret.info_type),
+                escape_md_cell(&secret.value).replace('`', "'"),
+                secret.url_count,
+            ));
+        }
+        md.push('\n');
+    }
+
+    // 响应体完全相同的URL分组（可折叠）
+    if !report.duplicate_bodies.is_empty() {
+        md.push_str("## 重复响应体\n\n");
+        for group in &report.duplicate_bodies {
+            md.push_str(&format!("<details>\n<summary>{} ({}个URL)</summary>\n\n", &group.hash[..group.hash.len().min(12)], group.urls.len()));
+            for url in &group.urls {
+                md.push_str(&format!("- {}\n", url));
+            }
+            md.push_str("\n</details>\n\n");
+        }
+    }
+
+    // 403 URL列表（可折叠）
+    if !report.forbidden_urls.is_empty() {
+        md.push_str("## 403禁止访问URL\n\n");
+        md.push_str(&format!("<details>\n<summary>共 {} 项</summary>\n\n", report.forbidden_urls.len()));
+        for url in &report.forbidden_urls {
+            md.push_str(&format!("- {}\n", url));
+        }
+        md.push_str("\n</details>\n");
+    }
+
+    md
+}
+
+pub fn save_markdown_report(output_path: &Path, report: &ComprehensiveScanReport) -> Result<(), ScanError> {
+    // 创建输出目录（如果不存在）
+    if let Some(parent) = output_path.parent() {
+        fs::create_dir_all(parent)
+            .map_err(|e| ScanError::IOError(format!("无法创建输出目录: {}", e)))?;
+    }
+
+    fs::write(output_path, render_markdown_report(report))
+        .map_err(|e| ScanError::ReportError(format!("写入Markdown报告失败: {}", e)))?;
+
+    Ok(())
+}
+
+// --summary-output 写出的精简摘要，字段与 print_summary 显示的统计一致，便于CI断言
+#[derive(Debug, Serialize)]
+pub struct ScanSummary<'a> {
+    pub target: &'a str,
+    pub scan_timestamp: &'a str,
+    pub scan_duration: u64,
+    pub partial: bool,
+    pub paths_scanned: usize,
+    pub findings: usize,
+    pub findings_by_type: BTreeMap<String, usize>,
+    pub server_errors: u32,
+    pub forbidden: usize,
+    pub overall_risk: &'a OverallRisk,
+}
+
+impl<'a> ScanSummary<'a> {
+    pub fn new(report: &'a ComprehensiveScanReport) -> Self {
+        ScanSummary {
+            target: &report.scan_config.target,
+            scan_timestamp: &report.scan_timestamp,
+            scan_duration: report.scan_duration,
+            partial: report.partial,
+            paths_scanned: report.scan_config.paths_scanned,
+            findings: findings_count(report),
+            findings_by_type: findings_by_type(report).into_iter().collect(),
+            server_errors: report.error_count,
+            forbidden: report.forbidden_urls.len(),
+            overall_risk: &report.overall_risk,
+        }
+    }
+}
+
+pub fn save_summary_report(output_path: &Path, report: &ComprehensiveScanReport) -> Result<(), ScanError> {
+    if let Some(parent) = output_path.parent() {
+        fs::create_dir_all(parent)
+            .map_err(|e| ScanError::IOError(format!("无法创建输出目录: {}", e)))?;
+    }
+
+    let json = serde_json::to_string_pretty(&ScanSummary::new(report))
+        .map_err(|e| ScanError::SerializationError(format!("序列化摘要失败: {}", e)))?;
+    fs::write(output_path, json)
+        .map_err(|e| ScanError::ReportError(format!("写入摘要文件失败: {}", e)))?;
+
+    Ok(())
+}
+
+// 转义XML属性和文本中的特殊字符，并去掉XML 1.0不允许的控制字符
+fn escape_xml(value: &str) -> String {
+    let mut escaped = String::with_capacity(value.len());
+    for c in value.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\'' => escaped.push_str("&apos;"),
+            '\t' | '\n' | '\r' => escaped.push(c),
+            c if (c as u32) < 0x20 => {}
+            c => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+// 生成JUnit XML报告：每个保留结果的路径(含403)为一个testcase，每项发现为其中的一个failure
+// suite的tests为请求总数，failures为有发现的路径数，errors为5xx数量
+pub fn render_junit_report(report: &ComprehensiveScanReport) -> String {
+    // 按 (URL, 请求方法) 分组，--methods 时每个方法的用例只包含该方法请求产生的发现；
+    // 旧报告中的发现没有记录方法，视为GET
+    let mut findings_by_request: HashMap<(&str, &str), Vec<&SensitiveInfoFinding>> = HashMap::new();
+    for finding in &report.sensitive_findings {
+        let method = finding.method.as_deref().unwrap_or("GET");
+        findings_by_request.entry((finding.url.as_str(), method)).or_default().push(finding);
+    }
+    let failures = report.basic_results.iter()
+        .filter(|r| findings_by_request.contains_key(&(r.url.as_str(), r.method.as_str())))
+        .count();
+
+    let mut xml = String::from("<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n");
+    xml.push_str(&format!(
+        "<testsuites>\n  <testsuite name=\"{}\" tests=\"{}\" failures=\"{}\" errors=\"{}\" time=\"{}\" timestamp=\"{}\">\n",
+        escape_xml(&report.scan_config.target),
+        report.basic_results.len() + report.forbidden_urls.len(),
+        failures,
+        report.error_count,
+        report.scan_duration,
+        escape_xml(&report.scan_timestamp),
+    ));
+
+    for result in &report.basic_results {
+        xml.push_str(&format!(
+            "    <testcase classname=\"{}\" name=\"{}\" time=\"{:.3}\">\n",
+            escape_xml(&report.scan_config.target),
+            escape_xml(&testcase_name(result)),
+            result.response_time as f64 / 1000.0,
+        ));
+        for finding in findings_by_request.get(&(result.url.as_str(), result.method.as_str())).into_iter().flatten() {
+            xml.push_str(&format!(
+                "      <failure type=\"{}\" message=\"{} (风险评分: {})\">{}</failure>\n",
+                escape_xml(&finding.info_type),
+                escape_xml(&finding.info_type),
+                finding.risk_score,
+                escape_xml(&finding.context),
+            ));
+        }
+        match &result.allow {
+            Some(allow) => xml.push_str(&format!("      <system-out>状态码: {} | Allow: {}</system-out>\n", result.status_code, escape_xml(allow))),
+            None => xml.push_str(&format!("      <system-out>状态码: {}</system-out>\n", result.status_code)),
+        }
+        xml.push_str("    </testcase>\n");
+    }
+
+    for url in &report.forbidden_urls {
+        xml.push_str(&format!(
+            "    <testcase classname=\"{}\" name=\"{}\">\n      <system-out>状态码: 403</system-out>\n    </testcase>\n",
+            escape_xml(&report.scan_config.target),
+            escape_xml(url),
+        ));
+    }
+
+    xml.push_str("  </testsuite>\n</testsuites>\n");
+    xml
+}
+
+pub fn save_junit_report(output_path: &Path, report: &ComprehensiveScanReport) -> Result<(), ScanError> {
+    if let Some(parent) = output_path.parent() {
+        fs::create_dir_all(parent)
+            .map_err(|e| ScanError::IOError(format!("无法创建输出目录: {}", e)))?;
+    }
+
+    fs::write(output_path, render_junit_report(report))
+        .map_err(|e| ScanError::ReportError(format!("写入JUnit报告失败: {}", e)))?;
+
+    Ok(())
+}
@@ -0,0 +1,54 @@
+// sitemap.rs
+// 解析 sitemap.xml 和 sitemap 索引，提取可加入扫描列表的路径
+use regex::Regex;
+use std::sync::OnceLock;
+use url::Url;
+
+// 解析结果：sitemap 索引中的 <loc> 是下一级 sitemap，普通 sitemap 中的是页面URL
+#[derive(Debug, Default)]
+pub struct Sitemap {
+    pub urls: Vec<String>,
+    pub sitemaps: Vec<String>,
+}
+
+fn loc_regex() -> &'static Regex {
+    static LOC: OnceLock<Regex> = OnceLock::new();
+    LOC.get_or_init(|| Regex::new(r"(?is)<loc>\s*(?:<!\[CDATA\[)?(.*?)(?:\]\]>)?\s*</loc>").unwrap())
+}
+
+// sitemap 只允许这五个预定义实体
+fn unescape_xml(value: &str) -> String {
+    value
+        .replace("&lt;", "<")
+        .replace("&gt;", ">")
+        .replace("&quot;", "\"")
+        .replace("&apos;", "'")
+        .replace("&amp;", "&")
+}
+
+pub fn parse_sitemap(xml: &str) -> Sitemap {
+    let locs = loc_regex()
+        .captures_iter(xml)
+        .map(|capture| unescape_xml(capture[1].trim()))
+        .filter(|loc| !loc.is_empty());
+    let mut sitemap = Sitemap::default();
+    if xml.contains("<sitemapindex") {
+        sitemap.sitemaps = locs.collect();
+    } else {
+        sitemap.urls = locs.collect();
+    }
+    sitemap
+}
+
+// 只保留位于 base_url 之下的URL，返回去掉 base_url 后的路径（含查询参数，不含片段）
+pub fn url_to_path(loc: &str, base_url: &str) -> Option<String> {
+    let mut url = Url::parse(loc).ok()?;
+    url.set_fragment(None);
+    let rest = url.as_str().strip_prefix(base_url)?;
+    match rest.chars().next() {
+        None => Some("/".to_string()),
+        Some('/') => Some(rest.to_string()),
+        Some('?') => Some(format!("/{}", rest)),
+        Some(_) => None,
+    }
+}
@@ -0,0 +1,130 @@
+// timing.rs
+// --detailed-timing：对每个目标主机单独建立一次全新连接，分别测量DNS解析、TCP连接、TLS握手和首字节时间
+// reqwest不暴露单个请求的各阶段耗时，而且连接池会复用连接，因此以这次探测代表该主机的建连开销
+use serde::{Serialize, Deserialize};
+use std::future::Future;
+use std::net::{IpAddr, SocketAddr};
+use std::time::{Duration, Instant};
+use tokio::io::{AsyncRead, AsyncReadExt, AsyncWrite, AsyncWriteExt};
+use tokio::net::TcpStream;
+use url::{Host, Url};
+
+// 单个主机的连接阶段耗时 (毫秒)，未执行或失败的阶段为空
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct HostTiming {
+    pub host: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub address: Option<String>,
+    pub dns_ms: Option<f64>,
+    pub connect_ms: Option<f64>,
+    pub tls_ms: Option<f64>,
+    pub ttfb_ms: Option<f64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+impl HostTiming {
+    // 建立连接的总耗时，用于找出握手慢的主机
+    pub fn handshake_ms(&self) -> f64 {
+        self.dns_ms.unwrap_or(0.0) + self.connect_ms.unwrap_or(0.0) + self.tls_ms.unwrap_or(0.0)
+    }
+}
+
+pub struct ProbeOptions<'a> {
+    pub timeout: Duration,
+    pub resolve: &'a [(String, IpAddr)],
+    pub host_header: Option<&'a str>,
+    pub user_agent: &'a str,
+}
+
+fn elapsed_ms(started: Instant) -> f64 {
+    (started.elapsed().as_secs_f64() * 10_000.0).round() / 10.0
+}
+
+async fn timed<T, E: ToString>(timeout: Duration, future: impl Future<Output = Result<T, E>>) -> Result<(T, f64), String> {
+    let started = Instant::now();
+    match tokio::time::timeout(timeout, future).await {
+        Ok(Ok(value)) => Ok((value, elapsed_ms(started))),
+        Ok(Err(e)) => Err(e.to_string()),
+        Err(_) => Err(format!("超过 {} 秒", timeout.as_secs())),
+    }
+}
+
+// 发送HEAD请求，读到第一个字节为止
+async fn first_byte<S: AsyncRead + AsyncWrite + Unpin>(stream: &mut S, request: &[u8]) -> std::io::Result<()> {
+    stream.write_all(request).await?;
+    let mut byte = [0u8; 1];
+    stream.read_exact(&mut byte).await?;
+    Ok(())
+}
+
+// 探测一个主机，某个阶段失败时记录错误并保留已完成阶段的耗时
+pub async fn probe_host(url: &Url, options: &ProbeOptions<'_>) -> HostTiming {
+    let port = url.port_or_known_default().unwrap_or(80);
+    let mut timing = HostTiming {
+        host: format!("{}:{}", url.host_str().unwrap_or(""), port),
+        ..HostTiming::default()
+    };
+    if let Err(e) = probe_phases(url, port, options, &mut timing).await {
+        timing.error = Some(e);
+    }
+    timing
+}
+
+async fn probe_phases(url: &Url, port: u16, options: &ProbeOptions<'_>, timing: &mut HostTiming) -> Result<(), String> {
+    let host = url.host().ok_or("URL缺少主机名")?;
+
+    // IP地址和 --resolve 固定的主机不需要DNS解析
+    let ip = match host {
+        Host::Ipv4(ip) => IpAddr::V4(ip),
+        Host::Ipv6(ip) => IpAddr::V6(ip),
+        Host::Domain(domain) => match options.resolve.iter().find(|(name, _)| name.eq_ignore_ascii_case(domain)) {
+            Some((_, ip)) => *ip,
+            None => {
+                let (mut addrs, ms) = timed(options.timeout, tokio::net::lookup_host((domain, port))).await
+                    .map_err(|e| format!("DNS解析失败: {}", e))?;
+                timing.dns_ms = Some(ms);
+                addrs.next().ok_or("DNS解析没有返回地址")?.ip()
+            }
+        },
+    };
+    let addr = SocketAddr::new(ip, port);
+    timing.address = Some(addr.to_string());
+
+    let (mut stream, ms) = timed(options.timeout, TcpStream::connect(addr)).await
+        .map_err(|e| format!("TCP连接失败: {}", e))?;
+    timing.connect_ms = Some(ms);
+
+    let authority = match url.port() {
+        Some(port) => format!("{}:{}", url.host_str().unwrap_or(""), port),
+        None => url.host_str().unwrap_or("").to_string(),
+    };
+    let request = format!(
+        "HEAD {} HTTP/1.1\r\nHost: {}\r\nUser-Agent: {}\r\nConnection: close\r\n\r\n",
+        url.path(),
+        options.host_header.unwrap_or(&authority),
+        options.user_agent,
+    );
+
+    if url.scheme() == "https" {
+        // 只测量握手耗时，不校验证书
+        let connector = native_tls::TlsConnector::builder()
+            .danger_accept_invalid_certs(true)
+            .danger_accept_invalid_hostnames(true)
+            .build()
+            .map_err(|e| format!("无法创建TLS连接器: {}", e))?;
+        let connector = tokio_native_tls::TlsConnector::from(connector);
+        let sni = url.host_str().unwrap_or("").trim_start_matches('[').trim_end_matches(']');
+        let (mut tls, ms) = timed(options.timeout, connector.connect(sni, stream)).await
+            .map_err(|e| format!("TLS握手失败: {}", e))?;
+        timing.tls_ms = Some(ms);
+        let (_, ms) = timed(options.timeout, first_byte(&mut tls, request.as_bytes())).await
+            .map_err(|e| format!("读取响应失败: {}", e))?;
+        timing.ttfb_ms = Some(ms);
+    } else {
+        let (_, ms) = timed(options.timeout, first_byte(&mut stream, request.as_bytes())).await
+            .map_err(|e| format!("读取响应失败: {}", e))?;
+        timing.ttfb_ms = Some(ms);
+    }
+    Ok(())
+}
@@ -0,0 +1,166 @@
+// tls.rs
+// --tls-info：对每个HTTPS主机单独建立一次TLS连接，读取服务器证书的主题、颁发者、有效期和SAN，
+// 并把自签名、已过期或即将过期的证书作为低风险发现写入报告
+use super::timing::ProbeOptions;
+use super::vulnerability::SensitiveInfoFinding;
+use openssl::asn1::Asn1Time;
+use openssl::nid::Nid;
+use openssl::x509::{X509, X509NameRef, X509VerifyResult};
+use serde::{Serialize, Deserialize};
+use std::net::{IpAddr, SocketAddr};
+use tokio::net::TcpStream;
+use url::{Host, Url};
+
+// 剩余有效期不超过该天数时报告即将过期
+pub const CERT_EXPIRY_WARN_DAYS: i32 = 30;
+
+// 单个主机的证书信息，连接或解析失败时只有 host 和 error
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct TlsCertInfo {
+    pub host: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub subject: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub issuer: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub not_before: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub not_after: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub days_remaining: Option<i32>, // 已过期时为负数
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub sans: Vec<String>,
+    #[serde(default)]
+    pub self_signed: bool,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+impl TlsCertInfo {
+    // 自签名、已过期和即将过期的证书各产生一项发现，URL为主机根路径
+    pub fn findings(&self) -> Vec<SensitiveInfoFinding> {
+        let url = format!("https://{}/", self.host);
+        let subject = self.subject.as_deref().unwrap_or("-");
+        let mut findings = Vec::new();
+        if self.self_signed {
+            findings.push(cert_finding(&url, "SelfSignedCertificate", subject.to_string(),
+                format!("证书由自身签发，颁发者: {}", self.issuer.as_deref().unwrap_or("-")), 3));
+        }
+        match self.days_remaining {
+            Some(days) if days < 0 => findings.push(cert_finding(&url, "CertificateExpired",
+                format!("{} 已于 {} 过期", subject, self.not_after.as_deref().unwrap_or("-")),
+                format!("证书已过期 {} 天", -days), 4)),
+            Some(days) if days <= CERT_EXPIRY_WARN_DAYS => findings.push(cert_finding(&url, "CertificateExpiringSoon",
+                format!("{} 将于 {} 过期", subject, self.not_after.as_deref().unwrap_or("-")),
+                format!("证书剩余有效期 {} 天", days), 3)),
+            _ => {}
+        }
+        findings
+    }
+}
+
+fn cert_finding(url: &str, info_type: &str, matched_content: String, context: String, risk_score: u8) -> SensitiveInfoFinding {
+    SensitiveInfoFinding {
+        info_type: info_type.to_string(),
+        url: url.to_string(),
+        matched_content,
+        position: 0,
+        context,
+        risk_score,
+        header: None,
+        payload: None,
+        json_path: None,
+        raw_content: None,
+        content_type: None,
+        method: None,
+    }
+}
+
+// 读取一个HTTPS主机的证书，失败时记录错误
+pub async fn fetch_certificate(url: &Url, options: &ProbeOptions<'_>) -> TlsCertInfo {
+    let port = url.port_or_known_default().unwrap_or(443);
+    let mut info = TlsCertInfo {
+        host: format!("{}:{}", url.host_str().unwrap_or(""), port),
+        ..TlsCertInfo::default()
+    };
+    match peer_certificate(url, port, options).await.and_then(|der| {
+        X509::from_der(&der).map_err(|e| format!("无法解析证书: {}", e))
+    }) {
+        Ok(cert) => fill_cert_info(&mut info, &cert),
+        Err(e) => info.error = Some(e),
+    }
+    info
+}
+
+async fn peer_certificate(url: &Url, port: u16, options: &ProbeOptions<'_>) -> Result<Vec<u8>, String> {
+    let host = url.host().ok_or("URL缺少主机名")?;
+    let connect = async {
+        match host {
+            Host::Ipv4(ip) => TcpStream::connect(SocketAddr::new(IpAddr::V4(ip), port)).await,
+            Host::Ipv6(ip) => TcpStream::connect(SocketAddr::new(IpAddr::V6(ip), port)).await,
+            Host::Domain(domain) => match options.resolve.iter().find(|(name, _)| name.eq_ignore_ascii_case(domain)) {
+                Some((_, ip)) => TcpStream::connect(SocketAddr::new(*ip, port)).await,
+                None => TcpStream::connect((domain, port)).await,
+            },
+        }
+    };
+    let stream = tokio::time::timeout(options.timeout, connect).await
+        .map_err(|_| format!("TCP连接超过 {} 秒", options.timeout.as_secs()))?
+        .map_err(|e| format!("TCP连接失败: {}", e))?;
+
+    // 需要读取无效证书的信息，因此不校验证书
+    let connector = native_tls::TlsConnector::builder()
+        .danger_accept_invalid_certs(true)
+        .danger_accept_invalid_hostnames(true)
+        .build()
+        .map_err(|e| format!("无法创建TLS连接器: {}", e))?;
+    let connector = tokio_native_tls::TlsConnector::from(connector);
+    let sni = url.host_str().unwrap_or("").trim_start_matches('[').trim_end_matches(']');
+    let tls = tokio::time::timeout(options.timeout, connector.connect(sni, stream)).await
+        .map_err(|_| format!("TLS握手超过 {} 秒", options.timeout.as_secs()))?
+        .map_err(|e| format!("TLS握手失败: {}", e))?;
+
+    let cert = tls.get_ref().peer_certificate()
+        .map_err(|e| format!("无法读取证书: {}", e))?
+        .ok_or("服务器没有提供证书")?;
+    cert.to_der().map_err(|e| format!("无法读取证书: {}", e))
+}
+
+fn fill_cert_info(info: &mut TlsCertInfo, cert: &X509) {
+    info.subject = Some(name_to_string(cert.subject_name()));
+    info.issuer = Some(name_to_string(cert.issuer_name()));
+    info.not_before = Some(cert.not_before().to_string());
+    info.not_after = Some(cert.not_after().to_string());
+    info.days_remaining = Asn1Time::days_from_now(0).ok()
+        .and_then(|now| now.diff(cert.not_after()).ok())
+        .map(|diff| diff.days);
+    info.sans = cert.subject_alt_names()
+        .map(|names| names.iter()
+            .filter_map(|name| name.dnsname().map(str::to_string).or_else(|| name.ipaddress().and_then(ip_to_string)))
+            .collect())
+        .unwrap_or_default();
+    info.self_signed = cert.issued(cert) == X509VerifyResult::OK
+        && cert.public_key().is_ok_and(|key| cert.verify(&key).unwrap_or(false));
+}
+
+// 按 "CN=example.com, O=Example" 的形式输出名称，优先显示CN
+fn name_to_string(name: &X509NameRef) -> String {
+    let mut parts: Vec<(bool, String)> = name.entries()
+        .map(|entry| {
+            let nid = entry.object().nid();
+            let key = nid.short_name().unwrap_or("?");
+            let value = entry.data().to_string().unwrap_or_default();
+            (nid != Nid::COMMONNAME, format!("{}={}", key, value))
+        })
+        .collect();
+    parts.sort_by_key(|(not_cn, _)| *not_cn);
+    parts.into_iter().map(|(_, part)| part).collect::<Vec<_>>().join(", ")
+}
+
+fn ip_to_string(bytes: &[u8]) -> Option<String> {
+    match bytes.len() {
+        4 => Some(IpAddr::from(<[u8; 4]>::try_from(bytes).ok()?).to_string()),
+        16 => Some(IpAddr::from(<[u8; 16]>::try_from(bytes).ok()?).to_string()),
+        _ => None,
+    }
+}
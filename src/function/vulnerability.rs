@@ -1,6 +1,15 @@
 // vulnerability.rs (仅保留敏感信息检测部分)
 use regex::Regex;
 use serde::{Serialize, Deserialize};
+use serde_json::Value;
+use std::collections::HashMap;
+use std::fs;
+use std::path::Path;
+use std::sync::OnceLock;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use super::ScanError;
+use super::decode::{mime_matches, mime_type};
+use log::warn;
 
 // 定义敏感信息结果结构体
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -11,16 +20,186 @@ pub struct SensitiveInfoFinding {
     pub position: usize,
     pub context: String,
     pub risk_score: u8,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub header: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub payload: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub json_path: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub content_type: Option<String>, // 响应体发现所在响应的MIME类型
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub method: Option<String>,       // 产生发现的请求方法，--methods 时用于把发现对应到结果
+    // --redact 时 matched_content 为遮盖后的显示值，原始值只保留在内存中用于去重统计，不写入任何输出
+    #[serde(skip)]
+    pub raw_content: Option<String>,
 }
 
+impl SensitiveInfoFinding {
+    // 原始匹配值；未脱敏或从已保存报告读取的发现即 matched_content
+    pub fn raw_value(&self) -> &str {
+        self.raw_content.as_deref().unwrap_or(&self.matched_content)
+    }
+
+    // 遮盖匹配值并从上下文中移除原始值，保留类型、风险评分和URL
+    pub fn redact(&mut self) {
+        if self.raw_content.is_some() {
+            return;
+        }
+        let raw = std::mem::take(&mut self.matched_content);
+        self.matched_content = mask_secret(&raw);
+        if !raw.is_empty() {
+            self.context = self.context.replace(&raw, REDACTED);
+        }
+        self.raw_content = Some(raw);
+    }
+}
+
+// 脱敏同一响应的全部发现：上下文可能包含其他发现的值，因此每条上下文都移除所有原始值
+pub fn redact_findings(findings: &mut [SensitiveInfoFinding]) {
+    for finding in findings.iter_mut() {
+        finding.redact();
+    }
+    let raws: Vec<String> = findings.iter()
+        .map(|f| f.raw_value().to_string())
+        .filter(|raw| !raw.is_empty())
+        .collect();
+    for finding in findings.iter_mut() {
+        for raw in &raws {
+            finding.context = scrub_context(&finding.context, raw);
+        }
+    }
+}
+
+// 上下文截取边界可能切断某个值，因此除完整出现外，开头的值后缀和结尾的值前缀也一并替换
+fn scrub_context(context: &str, raw: &str) -> String {
+    let mut context = context.replace(raw, REDACTED);
+    if let Some(cut) = (1..raw.len()).rev()
+        .filter(|&i| raw.is_char_boundary(i))
+        .find(|&i| context.ends_with(&raw[..i]))
+    {
+        context.truncate(context.len() - cut);
+        context.push_str(REDACTED);
+    }
+    if let Some(cut) = (1..raw.len())
+        .filter(|&i| raw.is_char_boundary(i))
+        .find(|&i| context.starts_with(&raw[i..]))
+    {
+        context.replace_range(..raw.len() - cut, REDACTED);
+    }
+    context
+}
+
+// 遮盖敏感值的中间部分，如 sk-live-****abcd
+pub fn mask_secret(value: &str) -> String {
+    let chars: Vec<char> = value.chars().collect();
+    let (prefix, suffix) = match chars.len() {
+        n if n >= 16 => (8, 4),
+        n if n >= 8 => (2, 2),
+        _ => (0, 0),
+    };
+    let head: String = chars[..prefix].iter().collect();
+    let tail: String = chars[chars.len() - suffix..].iter().collect();
+    format!("{}****{}", head, tail)
+}
+
+// 单条检测规则在本次扫描中产生的发现数（在误报抑制和风险阈值过滤之前统计）
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RuleStat {
+    pub rule: String,
+    pub matches: usize,
+}
+
+// 值为时间、长度等格式的常见响应头，跳过检测以避免误报
+const SKIPPED_HEADERS: [&str; 8] = [
+    "date", "expires", "last-modified", "content-length", "age", "etag", "content-type", "accept-ranges",
+];
+
+// --reflect-canary 附加的查询参数名，以及令牌后附加的HTML特殊字符 (请求中经过URL编码)
+pub const REFLECT_PARAM: &str = "apiscan_reflect";
+pub const REFLECT_SUFFIX: &str = "'\"<>";
+
+// 脱敏后的占位内容
+pub const REDACTED: &str = "[REDACTED]";
+
+// JSON键名检测的规则名，也是其发现的 info_type
+pub const JSON_KEY_RULE: &str = "sensitive_json_key";
+
+// JSON响应中默认视为敏感的键名，已按 normalize_key 规范化
+const DEFAULT_SENSITIVE_KEYS: [&str; 12] = [
+    "password", "passwd", "pwd", "secret", "clientsecret", "accesstoken",
+    "refreshtoken", "idtoken", "token", "apikey", "privatekey", "credential",
+];
+
 pub struct SensitiveInfoDetector {
     patterns: Vec<(String, Regex)>,
+    // 与patterns一一对应的命中次数，检测器在并发任务间共享
+    rule_matches: Vec<AtomicUsize>,
+    sensitive_keys: Vec<String>,
+    context_bytes: usize,
+    redact: bool,
+    // 规则名 -> 适用的MIME类型模式，未列出的规则适用于所有响应
+    rule_content_types: Vec<(String, Vec<String>)>,
+}
+
+impl Default for SensitiveInfoDetector {
+    fn default() -> Self {
+        Self::new()
+    }
 }
 
 impl SensitiveInfoDetector {
     pub fn new() -> Self {
+        let patterns = Self::create_patterns();
         SensitiveInfoDetector {
-            patterns: Self::create_patterns(),
+            rule_matches: patterns.iter().map(|_| AtomicUsize::new(0)).collect(),
+            patterns,
+            sensitive_keys: DEFAULT_SENSITIVE_KEYS.iter().map(|k| k.to_string()).collect(),
+            context_bytes: 30,
+            redact: false,
+            rule_content_types: Vec::new(),
+        }
+    }
+
+    // 设置上下文字节数，以及是否在上下文中隐去匹配值
+    pub fn with_context(mut self, context_bytes: usize, redact: bool) -> Self {
+        self.context_bytes = context_bytes;
+        self.redact = redact;
+        self
+    }
+
+    // 替换JSON敏感键名列表
+    pub fn with_sensitive_keys(mut self, keys: &[String]) -> Self {
+        self.sensitive_keys = keys.iter()
+            .map(|k| normalize_key(k))
+            .filter(|k| !k.is_empty())
+            .collect();
+        self
+    }
+
+    // 限定规则只对指定MIME类型的响应体生效，如 "sensitive_json_key=application/json|*+json"；
+    // 规则名可以是正则规则或 sensitive_json_key (JSON键名检测)，未知的规则名记录警告后忽略
+    pub fn with_rule_content_types(mut self, entries: &[String]) -> Self {
+        for entry in entries {
+            let Some((rule, types)) = entry.split_once('=') else { continue };
+            let rule = rule.trim();
+            if rule != JSON_KEY_RULE && !self.patterns.iter().any(|(name, _)| name == rule) {
+                warn!("--rule-content-types 中的规则不存在: {}", rule);
+                continue;
+            }
+            let types = types.split('|').map(|t| t.trim().to_string()).filter(|t| !t.is_empty()).collect();
+            self.rule_content_types.retain(|(name, _)| name != rule);
+            self.rule_content_types.push((rule.to_string(), types));
+        }
+        self
+    }
+
+    // 规则是否适用于该MIME类型，未声明类型的响应适用所有规则
+    fn rule_applies(&self, rule: &str, mime: Option<&str>) -> bool {
+        let Some(mime) = mime else { return true };
+        match self.rule_content_types.iter().find(|(name, _)| name == rule) {
+            Some((_, types)) => types.iter().any(|pattern| mime_matches(mime, pattern)),
+            None => true,
         }
     }
 
@@ -46,17 +225,22 @@ impl SensitiveInfoDetector {
     }
 
     pub fn detect(&self, url: &str, content: &str) -> Vec<SensitiveInfoFinding> {
+        self.detect_rules(url, content, None)
+    }
+
+    fn detect_rules(&self, url: &str, content: &str, mime: Option<&str>) -> Vec<SensitiveInfoFinding> {
         let mut findings = Vec::new();
-        for (pattern_name, regex) in &self.patterns {
+        for ((pattern_name, regex), matches) in self.patterns.iter().zip(&self.rule_matches) {
+            if !self.rule_applies(pattern_name, mime) {
+                continue;
+            }
             for capture in regex.captures_iter(content) {
                 if let Some(matched) = capture.get(0) {
                     let matched_text = matched.as_str();
                     let position = matched.start();
                     
                     // 提取上下文
-                    let context_start = position.saturating_sub(30);
-                    let context_end = (position + matched_text.len() + 30).min(content.len());
-                    let context_snippet = &content[context_start..context_end];
+                    let context_snippet = self.context_around(content, position, position + matched_text.len());
 
                     // 风险评分
                     let score = match pattern_name.as_str() {
@@ -69,13 +253,20 @@ impl SensitiveInfoDetector {
 
                     // 上下文分析，减少误报
                     if self.analyze_context(content, pattern_name, matched_text, position) {
+                        matches.fetch_add(1, Ordering::Relaxed);
                         findings.push(SensitiveInfoFinding {
                             info_type: pattern_name.clone(),
                             url: url.to_string(),
                             matched_content: matched_text.to_string(),
                             position,
-                            context: context_snippet.to_string(),
+                            context: context_snippet,
                             risk_score: score,
+                            header: None,
+                            payload: None,
+                            json_path: None,
+                            raw_content: None,
+                            content_type: None,
+                            method: None,
                         });
                     }
                 }
@@ -83,11 +274,121 @@ impl SensitiveInfoDetector {
         }
         findings
     }
+
+    // --reflect-canary：令牌后紧跟未编码的 REFLECT_SUFFIX 时报告 ReflectedInput，
+    // 只反射了令牌本身 (特殊字符已被编码或过滤) 不报告
+    pub fn detect_reflection(&self, url: &str, content: &str, token: &str) -> Option<SensitiveInfoFinding> {
+        let reflected = format!("{}{}", token, REFLECT_SUFFIX);
+        let position = content.find(&reflected)?;
+        Some(SensitiveInfoFinding {
+            info_type: "ReflectedInput".to_string(),
+            url: url.to_string(),
+            matched_content: format!("{}={}", REFLECT_PARAM, reflected),
+            position,
+            context: self.context_around(content, position, position + reflected.len()),
+            risk_score: 6,
+            header: None,
+            payload: None,
+            json_path: None,
+            raw_content: None,
+            content_type: None,
+            method: None,
+        })
+    }
+    
+    // 每条正则规则的命中次数，按规则定义顺序
+    pub fn rule_stats(&self) -> Vec<RuleStat> {
+        self.patterns.iter().zip(&self.rule_matches)
+            .map(|((rule, _), matches)| RuleStat {
+                rule: rule.clone(),
+                matches: matches.load(Ordering::Relaxed),
+            })
+            .collect()
+    }
+
+    // 截取匹配前后各 context_bytes 字节，边界向外对齐到字符边界，避免截断多字节字符
+    fn context_around(&self, content: &str, start: usize, end: usize) -> String {
+        let context_start = content.floor_char_boundary(start.saturating_sub(self.context_bytes));
+        let context_end = content.ceil_char_boundary((end + self.context_bytes).min(content.len()));
+        if self.redact {
+            format!("{}{}{}", &content[context_start..start], REDACTED, &content[end..context_end])
+        } else {
+            content[context_start..context_end].to_string()
+        }
+    }
+
+    // 按响应的Content-Type只应用适用的规则 (见 with_rule_content_types)，并在发现中记录MIME类型；
+    // 内容是有效JSON时还按键名检测敏感字段 (sensitive_json_key)，否则只做正则检测
+    pub fn detect_content(&self, url: &str, content: &str, content_type: Option<&str>) -> Vec<SensitiveInfoFinding> {
+        let mime = content_type.and_then(mime_type);
+        let mut findings = self.detect_rules(url, content, mime.as_deref());
+        if self.rule_applies(JSON_KEY_RULE, mime.as_deref())
+            && let Ok(value) = serde_json::from_str::<Value>(content)
+        {
+            self.walk_json(url, &value, "$", &mut findings);
+        }
+        for finding in &mut findings {
+            finding.content_type = mime.clone();
+        }
+        findings
+    }
+
+    fn walk_json(&self, url: &str, value: &Value, path: &str, findings: &mut Vec<SensitiveInfoFinding>) {
+        match value {
+            Value::Object(map) => {
+                for (key, child) in map {
+                    let child_path = format!("{}.{}", path, key);
+                    if self.sensitive_keys.contains(&normalize_key(key))
+                        && let Some(matched) = scalar_text(child)
+                    {
+                        findings.push(SensitiveInfoFinding {
+                            info_type: JSON_KEY_RULE.to_string(),
+                            url: url.to_string(),
+                            context: format!("{}: {}", child_path, if self.redact { REDACTED } else { matched.as_str() }),
+                            matched_content: matched,
+                            position: 0,
+                            risk_score: 8,
+                            header: None,
+                            payload: None,
+                            json_path: Some(child_path.clone()),
+                            raw_content: None,
+                            content_type: None,
+                            method: None,
+                        });
+                    }
+                    self.walk_json(url, child, &child_path, findings);
+                }
+            }
+            Value::Array(items) => {
+                for (i, child) in items.iter().enumerate() {
+                    self.walk_json(url, child, &format!("{}[{}]", path, i), findings);
+                }
+            }
+            _ => {}
+        }
+    }
+
+    // 对响应头的值应用同样的规则，发现中记录对应的头名称
+    pub fn detect_headers(&self, url: &str, headers: &reqwest::header::HeaderMap) -> Vec<SensitiveInfoFinding> {
+        let mut findings = Vec::new();
+        for (name, value) in headers {
+            if SKIPPED_HEADERS.contains(&name.as_str()) {
+                continue;
+            }
+            let value = String::from_utf8_lossy(value.as_bytes());
+            for mut finding in self.detect(url, &value) {
+                finding.context = format!("{}: {}", name, finding.context);
+                finding.header = Some(name.as_str().to_string());
+                findings.push(finding);
+            }
+        }
+        findings
+    }
     
     fn analyze_context(&self, content: &str, pattern_name: &str, matched_text: &str, position: usize) -> bool {
         // 获取匹配前后的上下文
-        let context_start = position.saturating_sub(50);
-        let context_end = (position + matched_text.len() + 50).min(content.len());
+        let context_start = content.floor_char_boundary(position.saturating_sub(50));
+        let context_end = content.ceil_char_boundary((position + matched_text.len() + 50).min(content.len()));
         let context = &content[context_start..context_end];
         
         // 基于上下文判断是否为误报
@@ -112,3 +413,290 @@ impl SensitiveInfoDetector {
         }
     }
 }
+
+// --check-cors 时随请求发送的Origin
+pub const CORS_PROBE_ORIGIN: &str = "https://cors-probe.example.com";
+
+// 检查CORS响应头：反射任意Origin，或通配符与允许凭据同时出现
+pub fn detect_cors(url: &str, headers: &reqwest::header::HeaderMap, origin: &str) -> Option<SensitiveInfoFinding> {
+    let header_value = |name: reqwest::header::HeaderName| {
+        headers.get(name).and_then(|v| v.to_str().ok()).map(str::trim)
+    };
+    let allow_origin = header_value(reqwest::header::ACCESS_CONTROL_ALLOW_ORIGIN)?;
+    let allow_credentials = header_value(reqwest::header::ACCESS_CONTROL_ALLOW_CREDENTIALS)
+        .is_some_and(|v| v.eq_ignore_ascii_case("true"));
+
+    let (reason, risk_score) = if allow_origin == origin {
+        if allow_credentials { ("反射任意Origin且允许携带凭据", 8) } else { ("反射任意Origin", 5) }
+    } else if allow_origin == "*" && allow_credentials {
+        ("通配符Origin且允许携带凭据", 6)
+    } else {
+        return None;
+    };
+
+    Some(SensitiveInfoFinding {
+        info_type: "PermissiveCORS".to_string(),
+        url: url.to_string(),
+        matched_content: format!("Access-Control-Allow-Origin: {}", allow_origin),
+        position: 0,
+        context: format!(
+            "{} (Origin: {}, Access-Control-Allow-Credentials: {})",
+            reason, origin, allow_credentials
+        ),
+        risk_score,
+        header: Some("access-control-allow-origin".to_string()),
+        payload: None,
+        json_path: None,
+        raw_content: None,
+        content_type: None,
+        method: None,
+    })
+}
+
+// 目录列表页面的典型标记 (小写)，覆盖Apache、nginx、IIS和Python http.server的自动索引页
+const DIRECTORY_LISTING_MARKERS: [&str; 8] = [
+    "<title>index of /",
+    "<h1>index of /",
+    "<title>directory listing for /",
+    "<h1>directory listing for /",
+    "parent directory</a>",
+    "[to parent directory]",
+    "href=\"../\"",
+    ">last modified</",
+];
+
+// 至少命中两个不同的标记才认为是目录列表，降低误报
+const DIRECTORY_LISTING_MIN_MARKERS: usize = 2;
+
+// 检查响应体是否为自动生成的目录列表页面
+pub fn detect_directory_listing(url: &str, body: &str) -> Option<SensitiveInfoFinding> {
+    let lower = body.to_ascii_lowercase();
+    let matched: Vec<(usize, &str)> = DIRECTORY_LISTING_MARKERS.iter()
+        .filter_map(|marker| lower.find(marker).map(|position| (position, *marker)))
+        .collect();
+    if matched.len() < DIRECTORY_LISTING_MIN_MARKERS {
+        return None;
+    }
+
+    // 匹配内容取最靠前的标记所在的原文
+    let (position, marker) = matched.iter().min_by_key(|(position, _)| *position).copied()?;
+    let markers = matched.iter().map(|(_, marker)| *marker).collect::<Vec<_>>().join(", ");
+    Some(SensitiveInfoFinding {
+        info_type: "DirectoryListing".to_string(),
+        url: url.to_string(),
+        matched_content: body[position..position + marker.len()].to_string(),
+        position,
+        context: format!("目录列表页面 (命中标记: {})", markers),
+        risk_score: 5,
+        header: None,
+        payload: None,
+        json_path: None,
+        raw_content: None,
+        content_type: None,
+        method: None,
+    })
+}
+
+// --auth-bypass-check：带认证的请求返回200后去掉认证头重新请求，未认证请求也返回200说明可能没有做访问控制；
+// 两次响应体完全相同时更可信。公开端点同样会命中，需要人工确认
+pub fn auth_bypass_finding(url: &str, status: u16, identical_body: bool) -> SensitiveInfoFinding {
+    let (reason, risk_score) = if identical_body {
+        ("带认证和不带认证的请求都返回200，且响应体完全相同", 8)
+    } else {
+        ("带认证和不带认证的请求都返回200，响应体不同", 6)
+    };
+    SensitiveInfoFinding {
+        info_type: "PossibleAuthBypass".to_string(),
+        url: url.to_string(),
+        matched_content: format!("未认证请求返回 {}", status),
+        position: 0,
+        context: reason.to_string(),
+        risk_score,
+        header: None,
+        payload: None,
+        json_path: None,
+        raw_content: None,
+        content_type: None,
+        method: None,
+    }
+}
+
+// 本身就不应公开访问的文件路径：版本库元数据、环境变量文件、凭据文件和备份/归档文件
+const EXPOSED_FILE_PATTERNS: [&str; 8] = [
+    r"/\.git/(config|HEAD|index|logs/HEAD)$",
+    r"/\.(svn/(entries|wc\.db)|hg/(hgrc|store/.*))$",
+    r"/\.env(\.[\w.-]+)?$",
+    r"/\.(htpasswd|htaccess|DS_Store|npmrc|pypirc|netrc|bash_history|git-credentials)$",
+    r"/(id_rsa|id_dsa|id_ecdsa|id_ed25519)$",
+    r"/(web|wp-config|config|settings|database)\.\w+\.(bak|old|orig|save|swp)$",
+    r"\.(zip|rar|7z|tar|tgz|tar\.gz|gz|bak|backup|old|orig|swp|sql|sql\.gz|dump)$",
+    r"~$",
+];
+
+fn exposed_file_regexes() -> &'static [Regex] {
+    static REGEXES: OnceLock<Vec<Regex>> = OnceLock::new();
+    REGEXES.get_or_init(|| EXPOSED_FILE_PATTERNS.iter()
+        .map(|pattern| Regex::new(&format!("(?i){}", pattern)).unwrap())
+        .collect())
+}
+
+// 按请求路径 (不含查询参数) 判断是否为敏感文件，只要返回200就报告，与响应内容无关
+pub fn detect_exposed_file(url: &str, path: &str) -> Option<SensitiveInfoFinding> {
+    let path = path.split(['?', '#']).next().unwrap_or(path);
+    let pattern = exposed_file_regexes().iter().find(|regex| regex.is_match(path))?;
+    Some(SensitiveInfoFinding {
+        info_type: "ExposedSensitiveFile".to_string(),
+        url: url.to_string(),
+        matched_content: path.to_string(),
+        position: 0,
+        context: format!("敏感文件路径返回200 (规则: {})", pattern.as_str().trim_start_matches("(?i)")),
+        risk_score: 10,
+        header: None,
+        payload: None,
+        json_path: None,
+        raw_content: None,
+        content_type: None,
+        method: None,
+    })
+}
+
+// 键名比较忽略大小写以及 _ 和 -，使 accessToken、access_token、Access-Token 等价
+fn normalize_key(key: &str) -> String {
+    key.chars()
+        .filter(|c| *c != '_' && *c != '-')
+        .flat_map(char::to_lowercase)
+        .collect()
+}
+
+// 只报告非空的标量值，null、空字符串和嵌套结构不算泄露
+fn scalar_text(value: &Value) -> Option<String> {
+    match value {
+        Value::String(s) if !s.is_empty() => Some(s.clone()),
+        Value::Number(n) => Some(n.to_string()),
+        Value::Bool(b) => Some(b.to_string()),
+        _ => None,
+    }
+}
+
+// 误报抑制列表：匹配内容命中列表中的字面值或正则时丢弃该发现
+// 文件每行一项，以 re: 开头的按正则处理，以 # 开头的为注释
+#[derive(Default)]
+pub struct FindingSuppressor {
+    literals: Vec<String>,
+    regexes: Vec<Regex>,
+    suppressed: AtomicUsize,
+}
+
+impl FindingSuppressor {
+    pub fn load(path: &Path) -> Result<Self, ScanError> {
+        let content = fs::read_to_string(path)
+            .map_err(|e| ScanError::IOError(format!("无法读取误报抑制列表: {}", e)))?;
+
+        let mut suppressor = FindingSuppressor::default();
+        for line in content.lines().map(str::trim) {
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            match line.strip_prefix("re:") {
+                Some(pattern) => {
+                    let regex = Regex::new(pattern.trim())
+                        .map_err(|e| ScanError::InvalidConfig(format!("误报抑制正则无效 ({}): {}", pattern, e)))?;
+                    suppressor.regexes.push(regex);
+                }
+                None => suppressor.literals.push(line.to_string()),
+            }
+        }
+        Ok(suppressor)
+    }
+
+    fn is_suppressed(&self, finding: &SensitiveInfoFinding) -> bool {
+        let value = finding.matched_content.as_str();
+        self.literals.iter().any(|literal| literal == value)
+            || self.regexes.iter().any(|regex| regex.is_match(value))
+    }
+
+    // 过滤发现并累计被抑制的数量
+    pub fn apply(&self, findings: Vec<SensitiveInfoFinding>) -> Vec<SensitiveInfoFinding> {
+        if self.literals.is_empty() && self.regexes.is_empty() {
+            return findings;
+        }
+        let before = findings.len();
+        let kept: Vec<_> = findings.into_iter().filter(|f| !self.is_suppressed(f)).collect();
+        self.suppressed.fetch_add(before - kept.len(), Ordering::Relaxed);
+        kept
+    }
+
+    pub fn suppressed_count(&self) -> usize {
+        self.suppressed.load(Ordering::Relaxed)
+    }
+}
+
+// 按info_type重新指定风险评分，在风险阈值过滤之前应用
+// 文件每行一项 "info_type = 评分"，类型不区分大小写，评分为0-10，以 # 开头的为注释；未列出的类型保持默认评分
+#[derive(Default)]
+pub struct RiskOverrides {
+    scores: HashMap<String, u8>,
+}
+
+impl RiskOverrides {
+    pub fn load(path: &Path) -> Result<Self, ScanError> {
+        let content = fs::read_to_string(path)
+            .map_err(|e| ScanError::IOError(format!("无法读取风险评分覆盖文件: {}", e)))?;
+
+        let mut overrides = RiskOverrides::default();
+        for line in content.lines().map(str::trim) {
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            let score = line.split_once('=')
+                .map(|(info_type, score)| (info_type.trim(), score.trim()))
+                .filter(|(info_type, _)| !info_type.is_empty())
+                .and_then(|(info_type, score)| score.parse::<u8>().ok().filter(|s| *s <= 10).map(|s| (info_type, s)));
+            match score {
+                Some((info_type, score)) => {
+                    overrides.scores.insert(info_type.to_lowercase(), score);
+                }
+                None => return Err(ScanError::InvalidConfig(format!("风险评分覆盖格式应为 类型 = 0-10 的评分: {}", line))),
+            }
+        }
+        Ok(overrides)
+    }
+
+    pub fn apply(&self, mut findings: Vec<SensitiveInfoFinding>) -> Vec<SensitiveInfoFinding> {
+        for finding in &mut findings {
+            if let Some(score) = self.scores.get(&finding.info_type.to_lowercase()) {
+                finding.risk_score = *score;
+            }
+        }
+        findings
+    }
+}
+
+// 风险评分阈值：丢弃低于阈值的发现并累计数量，阈值为0时不过滤
+pub struct RiskFilter {
+    min_risk: u8,
+    filtered: AtomicUsize,
+}
+
+impl RiskFilter {
+    pub fn new(min_risk: u8) -> Self {
+        RiskFilter {
+            min_risk,
+            filtered: AtomicUsize::new(0),
+        }
+    }
+
+    pub fn apply(&self, findings: Vec<SensitiveInfoFinding>) -> Vec<SensitiveInfoFinding> {
+        if self.min_risk == 0 {
+            return findings;
+        }
+        let before = findings.len();
+        let kept: Vec<_> = findings.into_iter().filter(|f| f.risk_score >= self.min_risk).collect();
+        self.filtered.fetch_add(before - kept.len(), Ordering::Relaxed);
+        kept
+    }
+
+    pub fn filtered_count(&self) -> usize {
+        self.filtered.load(Ordering::Relaxed)
+    }
+}
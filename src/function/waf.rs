@@ -0,0 +1,81 @@
+// waf.rs
+// --waf-check：扫描前分别发送一个正常请求和一个带典型攻击特征的请求，
+// 只有后者被拦截时判断目标前面可能有WAF，避免整个字典都被拦截后才发现
+use serde::{Serialize, Deserialize};
+
+// 探测请求附加的查询参数：SQL注入、XSS和路径遍历特征 (已URL编码)
+pub const WAF_PROBE_QUERY: &str = "id=1%27%20OR%20%271%27%3D%271&q=%3Cscript%3Ealert(1)%3C%2Fscript%3E&file=..%2F..%2F..%2Fetc%2Fpasswd";
+
+// 判断是否被拦截时最多检查的响应体字节数
+pub const WAF_BODY_LIMIT: usize = 64 * 1024;
+
+// WAF常用的拦截状态码
+const BLOCK_STATUSES: [u16; 3] = [403, 406, 429];
+
+// 拦截页和人机验证页中的常见特征 (小写)
+const BLOCK_MARKERS: [&str; 11] = [
+    "captcha", "cf-chl", "challenge-platform", "attention required", "access denied",
+    "request rejected", "request blocked", "_incapsula_", "web application firewall", "mod_security", "aws waf",
+];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum WafVerdict {
+    // 两个请求都没有被拦截
+    NotDetected,
+    // 正常请求成功而攻击特征请求被拦截
+    Likely,
+    // 正常请求也被拦截，目标可能已经封禁了本机
+    AllBlocked,
+    // 正常请求失败，无法判断
+    Inconclusive,
+}
+
+// 单个探测请求的结果，请求失败时 status 为空
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct WafProbeResult {
+    pub url: String,
+    pub status: Option<u16>,
+    pub blocked: bool,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub reason: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WafCheck {
+    pub verdict: WafVerdict,
+    pub benign: WafProbeResult,
+    pub probe: WafProbeResult,
+}
+
+impl WafCheck {
+    pub fn new(benign: WafProbeResult, probe: WafProbeResult) -> Self {
+        let verdict = match (benign.status, benign.blocked, probe.blocked) {
+            (None, _, _) => WafVerdict::Inconclusive,
+            (Some(_), true, _) => WafVerdict::AllBlocked,
+            (Some(_), false, true) => WafVerdict::Likely,
+            (Some(_), false, false) => WafVerdict::NotDetected,
+        };
+        WafCheck { verdict, benign, probe }
+    }
+
+    pub fn description(&self) -> &'static str {
+        match self.verdict {
+            WafVerdict::NotDetected => "未发现拦截",
+            WafVerdict::Likely => "可能存在WAF：带攻击特征的请求被拦截，建议降低 --concurrency 或设置 --delay-ms",
+            WafVerdict::AllBlocked => "正常请求也被拦截，目标可能已封禁本机或要求人机验证",
+            WafVerdict::Inconclusive => "正常请求失败，无法判断",
+        }
+    }
+}
+
+// 按状态码和响应体特征判断是否被拦截，返回拦截原因
+pub fn block_reason(status: u16, body: &str) -> Option<String> {
+    if BLOCK_STATUSES.contains(&status) {
+        return Some(format!("状态码 {}", status));
+    }
+    let lower = body.to_lowercase();
+    BLOCK_MARKERS.iter()
+        .find(|marker| lower.contains(*marker))
+        .map(|marker| format!("响应包含 \"{}\"", marker))
+}
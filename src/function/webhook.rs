@@ -0,0 +1,62 @@
+// webhook.rs
+// 扫描结束后将达到阈值的发现以JSON POST到指定URL，便于接入聊天机器人或工单系统
+use super::scanner::{ComprehensiveScanReport, OverallRisk};
+use super::vulnerability::SensitiveInfoFinding;
+use log::{info, warn};
+use reqwest::Client;
+use serde::Serialize;
+use std::time::Duration;
+
+// 发送失败时的最大尝试次数，两次尝试之间依次等待1、2秒
+const WEBHOOK_MAX_ATTEMPTS: u32 = 3;
+
+#[derive(Debug, Serialize)]
+pub struct WebhookPayload<'a> {
+    pub target: &'a str,
+    pub scan_timestamp: &'a str,
+    pub paths_scanned: usize,
+    pub partial: bool,
+    pub overall_risk: &'a OverallRisk,
+    pub min_risk: u8,
+    pub findings: Vec<&'a SensitiveInfoFinding>,
+}
+
+impl<'a> WebhookPayload<'a> {
+    pub fn new(report: &'a ComprehensiveScanReport, min_risk: u8) -> Self {
+        WebhookPayload {
+            target: &report.scan_config.target,
+            scan_timestamp: &report.scan_timestamp,
+            paths_scanned: report.scan_config.paths_scanned,
+            partial: report.partial,
+            overall_risk: &report.overall_risk,
+            min_risk,
+            findings: report.sensitive_findings.iter()
+                .filter(|f| f.risk_score >= min_risk)
+                .collect(),
+        }
+    }
+}
+
+// 没有达到阈值的发现时不发送；发送失败只记录警告，不影响扫描结果
+pub async fn send_webhook(client: &Client, url: &str, min_risk: u8, report: &ComprehensiveScanReport) {
+    let payload = WebhookPayload::new(report, min_risk);
+    if payload.findings.is_empty() {
+        info!("没有风险评分不低于 {} 的发现，不发送webhook", min_risk);
+        return;
+    }
+
+    for attempt in 1..=WEBHOOK_MAX_ATTEMPTS {
+        match client.post(url).json(&payload).send().await {
+            Ok(response) if response.status().is_success() => {
+                info!("webhook已发送: {} ({} 项发现)", url, payload.findings.len());
+                return;
+            }
+            Ok(response) => warn!("webhook发送失败 ({}/{}): 状态码 {}", attempt, WEBHOOK_MAX_ATTEMPTS, response.status()),
+            Err(e) => warn!("webhook发送失败 ({}/{}): {}", attempt, WEBHOOK_MAX_ATTEMPTS, e),
+        }
+        if attempt < WEBHOOK_MAX_ATTEMPTS {
+            tokio::time::sleep(Duration::from_secs(1 << (attempt - 1))).await;
+        }
+    }
+    warn!("webhook在 {} 次尝试后仍发送失败: {}", WEBHOOK_MAX_ATTEMPTS, url);
+}
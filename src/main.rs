@@ -1,18 +1,40 @@
-use anyhow::Result;
-use structopt::StructOpt;
-use api_scan::function::scanner::run_scan;
+use api_scan::function::scanner::{run_scan, report_exit_code, error_exit_code, EXIT_OK, EXIT_FINDINGS, EXIT_INTERRUPTED};
 use api_scan::function::config::Config;
+use api_scan::function::logging::init_logger;
 
 #[tokio::main]
-async fn main() -> Result<()> {
+async fn main() {
     // 1. 解析命令行参数
-    let config = Config::from_args();
+    let config = match Config::from_args_with_config_file() {
+        Ok(config) => config,
+        Err(e) => {
+            eprintln!("错误: {}", e);
+            std::process::exit(error_exit_code(&e));
+        }
+    };
+    init_logger(config.verbose, config.quiet);
+    let fail_on = config.fail_on;
+    let quiet = config.quiet;
     
     // 2. 执行扫描
-    let _results = run_scan(config).await?;
+    let results = match run_scan(config).await {
+        Ok(results) => results,
+        Err(e) => {
+            eprintln!("错误: {}", e);
+            std::process::exit(error_exit_code(&e));
+        }
+    };
     
-    // 3. 显示摘要
-    println!("扫描完成！");
-    Ok(())
+    // 3. 根据结果设置退出码
+    let code = report_exit_code(&results, fail_on);
+    if !quiet {
+        match code {
+            EXIT_INTERRUPTED => println!("扫描已中断，部分报告已保存。"),
+            EXIT_FINDINGS => println!("扫描完成！存在风险评分不低于 {} 的发现。", fail_on.unwrap_or_default()),
+            _ => println!("扫描完成！"),
+        }
+    }
+    if code != EXIT_OK {
+        std::process::exit(code);
+    }
 }
-